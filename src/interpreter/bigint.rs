@@ -0,0 +1,294 @@
+use std::{
+    cmp::Ordering,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+/*
+* Arbitrary-precision integer
+* */
+
+// each limb holds 9 decimal digits, which keeps display simple
+const BASE: u64 = 1_000_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigInt {
+    negative: bool,
+    // magnitude, least significant limb first, no trailing zero limbs (zero is empty)
+    limbs: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn from_i64(x: i64) -> Self {
+        let mut rest = x.unsigned_abs();
+        let mut limbs = Vec::new();
+        while rest > 0 {
+            limbs.push((rest % BASE) as u32);
+            rest /= BASE;
+        }
+        BigInt::new(x < 0, limbs)
+    }
+
+    // decimal digits with an optional sign, e.g. "-123", None if malformed
+    pub fn parse(s: &str) -> Option<Self> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let digits = digits.as_bytes();
+        let mut limbs = Vec::new();
+        let mut end = digits.len();
+        while end > 0 {
+            let start = end.saturating_sub(9);
+            let chunk = std::str::from_utf8(&digits[start..end]).ok()?;
+            limbs.push(chunk.parse().ok()?);
+            end = start;
+        }
+        Some(BigInt::new(negative, limbs))
+    }
+
+    // truncates towards zero, None for NaN and infinity
+    pub fn from_f64(x: f64) -> Option<Self> {
+        if !x.is_finite() {
+            return None;
+        }
+        let x = x.trunc();
+        if x.abs() < i64::MAX as f64 {
+            return Some(BigInt::from_i64(x as i64));
+        }
+        // x = mantissa * 2^exponent, with an integer mantissa
+        let bits = x.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as i64 - 1075;
+        let mantissa = (bits & ((1 << 52) - 1)) | (1 << 52);
+        let mut res = BigInt::from_i64(mantissa as i64);
+        let mut remaining = exponent;
+        while remaining > 0 {
+            let step = remaining.min(30);
+            res.limbs = mul_small(&res.limbs, 1 << step);
+            remaining -= step;
+        }
+        res.negative = x < 0.0;
+        Some(res)
+    }
+
+    // None if the value does not fit
+    pub fn to_i64(&self) -> Option<i64> {
+        let magnitude = self.limbs.iter().rev().try_fold(0i128, |acc, limb| {
+            let acc = acc * BASE as i128 + *limb as i128;
+            (acc <= i64::MAX as i128 + 1).then_some(acc)
+        })?;
+        let value = if self.negative { -magnitude } else { magnitude };
+        i64::try_from(value).ok()
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self
+            .limbs
+            .iter()
+            .rev()
+            .fold(0.0, |acc, limb| acc * BASE as f64 + *limb as f64);
+        if self.negative { -magnitude } else { magnitude }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    // truncating division, None when dividing by zero
+    pub fn checked_div(&self, other: &BigInt) -> Option<BigInt> {
+        let (quotient, _) = self.div_rem(other)?;
+        Some(quotient)
+    }
+
+    // the remainder has the sign of the dividend, None when dividing by zero
+    pub fn checked_rem(&self, other: &BigInt) -> Option<BigInt> {
+        let (_, remainder) = self.div_rem(other)?;
+        Some(remainder)
+    }
+
+    pub fn display(&self) -> String {
+        let Some(last) = self.limbs.last() else {
+            return "0".to_string();
+        };
+        let mut res = if self.negative { "-" } else { "" }.to_string();
+        res.push_str(&last.to_string());
+        for limb in self.limbs.iter().rev().skip(1) {
+            res.push_str(&format!("{:09}", limb));
+        }
+        res
+    }
+
+    fn new(negative: bool, mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        // there is no negative zero
+        let negative = negative && !limbs.is_empty();
+        BigInt { negative, limbs }
+    }
+
+    fn div_rem(&self, other: &BigInt) -> Option<(BigInt, BigInt)> {
+        if other.is_zero() {
+            return None;
+        }
+        let (quotient, remainder) = div_rem_magnitude(&self.limbs, &other.limbs);
+        Some((
+            BigInt::new(self.negative != other.negative, quotient),
+            BigInt::new(self.negative, remainder),
+        ))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.limbs, &other.limbs),
+            (true, true) => cmp_magnitude(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/*
+* Operator implementations
+* */
+
+impl Add for BigInt {
+    type Output = BigInt;
+    fn add(self, rhs: BigInt) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt::new(self.negative, add_magnitude(&self.limbs, &rhs.limbs));
+        }
+        // different signs: subtract the smaller magnitude from the larger one
+        match cmp_magnitude(&self.limbs, &rhs.limbs) {
+            Ordering::Less => BigInt::new(rhs.negative, sub_magnitude(&rhs.limbs, &self.limbs)),
+            _ => BigInt::new(self.negative, sub_magnitude(&self.limbs, &rhs.limbs)),
+        }
+    }
+}
+
+impl Sub for BigInt {
+    type Output = BigInt;
+    fn sub(self, rhs: BigInt) -> BigInt {
+        self + -rhs
+    }
+}
+
+impl Mul for BigInt {
+    type Output = BigInt;
+    fn mul(self, rhs: BigInt) -> BigInt {
+        let mut res = vec![0u64; self.limbs.len() + rhs.limbs.len()];
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0;
+            for (j, b) in rhs.limbs.iter().enumerate() {
+                let cur = res[i + j] + *a as u64 * *b as u64 + carry;
+                res[i + j] = cur % BASE;
+                carry = cur / BASE;
+            }
+            res[i + rhs.limbs.len()] += carry;
+        }
+        let limbs = res.into_iter().map(|x| x as u32).collect();
+        BigInt::new(self.negative != rhs.negative, limbs)
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.limbs)
+    }
+}
+
+/*
+* Helper functions (on magnitudes)
+* */
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut res = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0;
+    for i in 0..a.len().max(b.len()) {
+        let cur = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        res.push((cur % BASE) as u32);
+        carry = cur / BASE;
+    }
+    res.push(carry as u32);
+    res
+}
+
+// expects a >= b
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut res = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    for (i, x) in a.iter().enumerate() {
+        let mut cur = *x as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if cur < 0 {
+            cur += BASE as i64;
+            borrow = 1;
+        }
+        res.push(cur as u32);
+    }
+    res
+}
+
+fn mul_small(a: &[u32], factor: u64) -> Vec<u32> {
+    let mut res = Vec::with_capacity(a.len() + 2);
+    let mut carry = 0;
+    for x in a {
+        let cur = *x as u64 * factor + carry;
+        res.push((cur % BASE) as u32);
+        carry = cur / BASE;
+    }
+    while carry > 0 {
+        res.push((carry % BASE) as u32);
+        carry /= BASE;
+    }
+    trim(res)
+}
+
+fn trim(mut limbs: Vec<u32>) -> Vec<u32> {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    limbs
+}
+
+// schoolbook long division, one limb of the quotient at a time
+fn div_rem_magnitude(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let mut quotient = vec![0; a.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+    for i in (0..a.len()).rev() {
+        remainder.insert(0, a[i]);
+        remainder = trim(remainder);
+        // largest digit q with b * q <= remainder
+        let (mut low, mut high) = (0, BASE - 1);
+        while low < high {
+            let mid = (low + high).div_ceil(2);
+            if cmp_magnitude(&mul_small(b, mid), &remainder) == Ordering::Greater {
+                high = mid - 1;
+            } else {
+                low = mid;
+            }
+        }
+        if low > 0 {
+            remainder = trim(sub_magnitude(&remainder, &mul_small(b, low)));
+        }
+        quotient[i] = low as u32;
+    }
+    (quotient, remainder)
+}
@@ -1,4 +1,8 @@
-use crate::{interpreter::scope::Scope, parser::ast::Function};
+use crate::{
+    interpreter::{exec_result::ExecResult, scope::Scope, value::Value},
+    parser::ast::Function,
+    utils::lang_error::Error,
+};
 use std::rc::Rc;
 
 /**
@@ -9,4 +13,15 @@ use std::rc::Rc;
 pub struct Closure {
     pub node: Function,
     pub env: Rc<Scope>,
+    // the file it was defined in, errors in its body name it (None outside of loaded programs)
+    pub file: Option<Rc<str>>,
+}
+
+pub type NativeFn = fn(Vec<Value>) -> Result<ExecResult, Error>;
+
+// a stdlib function, calls with fewer than `arity` arguments are partial applications
+#[derive(Debug, Clone, Copy)]
+pub struct Native {
+    pub function: NativeFn,
+    pub arity: usize,
 }
@@ -8,6 +8,9 @@ use crate::{interpreter::value::Value, utils::lang_error::Error};
 pub enum ExecResult {
     Value(Value),
     Returned(Value),
+    // unwinds to the innermost loop
+    Break,
+    Continue,
 }
 
 impl ExecResult {
@@ -12,7 +12,7 @@ pub struct Frame {
 
 impl Frame {
     pub fn new(ast: StatementList) -> Self {
-        Frame { ast: ast, pos: 0 }
+        Frame { ast, pos: 0 }
     }
 
     pub fn peek(&self) -> Option<&Statement> {
@@ -0,0 +1,91 @@
+/*
+* Grapheme clusters
+* */
+
+// splits a string into user-perceived characters, a simplified version of the
+// extended grapheme cluster rules (UAX #29) without any lookup tables:
+// combining marks, variation selectors and emoji modifiers stay with the character before them,
+// zero width joiner sequences (e.g. family emoji) and pairs of regional indicators (flags) are kept
+// together, and "\r\n" is a single cluster
+pub fn graphemes(s: &str) -> Vec<&str> {
+    let mut res = Vec::new();
+    let mut start = 0;
+    let mut prev: Option<char> = None;
+    // regional indicators seen in a row, flags are pairs
+    let mut regional = 0;
+    for (i, c) in s.char_indices() {
+        if let Some(p) = prev
+            && !joins(p, c, regional)
+        {
+            res.push(&s[start..i]);
+            start = i;
+            regional = 0;
+        }
+        if is_regional_indicator(c) {
+            regional += 1;
+        }
+        prev = Some(c);
+    }
+    if start < s.len() {
+        res.push(&s[start..]);
+    }
+    res
+}
+
+// true if `c` belongs to the same cluster as the character before it
+fn joins(prev: char, c: char, regional: usize) -> bool {
+    match (prev, c) {
+        ('\r', '\n') => true,
+        (p, _) if p.is_control() => false,
+        (_, c) if c.is_control() => false,
+        // zero width joiner: joins with both neighbours
+        ('\u{200D}', _) | (_, '\u{200D}') => true,
+        (p, c) if is_regional_indicator(p) && is_regional_indicator(c) => regional % 2 == 1,
+        (_, c) => is_extend(c),
+    }
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c, '\u{1F1E6}'..='\u{1F1FF}')
+}
+
+// combining marks of common scripts, hangul vowel and final jamo, variation selectors,
+// emoji modifiers and tags
+fn is_extend(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}'
+        | '\u{0483}'..='\u{0489}'
+        | '\u{0591}'..='\u{05BD}'
+        | '\u{05BF}'
+        | '\u{05C1}'..='\u{05C2}'
+        | '\u{05C4}'..='\u{05C5}'
+        | '\u{05C7}'
+        | '\u{0610}'..='\u{061A}'
+        | '\u{064B}'..='\u{065F}'
+        | '\u{0670}'
+        | '\u{06D6}'..='\u{06DC}'
+        | '\u{06DF}'..='\u{06E4}'
+        | '\u{06E7}'..='\u{06E8}'
+        | '\u{06EA}'..='\u{06ED}'
+        | '\u{0900}'..='\u{0903}'
+        | '\u{093A}'..='\u{093C}'
+        | '\u{093E}'..='\u{094F}'
+        | '\u{0951}'..='\u{0957}'
+        | '\u{0962}'..='\u{0963}'
+        | '\u{0E31}'
+        | '\u{0E34}'..='\u{0E3A}'
+        | '\u{0E47}'..='\u{0E4E}'
+        | '\u{1160}'..='\u{11FF}'
+        | '\u{1AB0}'..='\u{1AFF}'
+        | '\u{1DC0}'..='\u{1DFF}'
+        | '\u{200C}'
+        | '\u{20D0}'..='\u{20FF}'
+        | '\u{302A}'..='\u{302F}'
+        | '\u{3099}'..='\u{309A}'
+        | '\u{FE00}'..='\u{FE0F}'
+        | '\u{FE20}'..='\u{FE2F}'
+        | '\u{1F3FB}'..='\u{1F3FF}'
+        | '\u{E0020}'..='\u{E007F}'
+        | '\u{E0100}'..='\u{E01EF}'
+    )
+}
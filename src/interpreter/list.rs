@@ -1,4 +1,4 @@
-use crate::interpreter::value::Value;
+use crate::{interpreter::value::Value, parser::ast::Type};
 
 /*
 * List type
@@ -11,6 +11,17 @@ pub enum List {
 }
 
 impl List {
+    pub fn from_values(values: Vec<Value>) -> Self {
+        values.into_iter().rev().fold(List::Nil, |tail, head| {
+            let length = tail.length() + 1;
+            List::Cons(Cons {
+                head: Box::new(head),
+                tail: Box::new(tail),
+                length,
+            })
+        })
+    }
+
     pub fn display(&self, initial: bool) -> String {
         let open = if initial { "[" } else { "" };
         match self {
@@ -19,6 +30,25 @@ impl List {
         }
     }
 
+    // the element type is taken from the head (lists are homogeneous)
+    pub fn get_type(&self) -> Type {
+        match self {
+            List::Nil => Type::List(None),
+            List::Cons(c) => Type::List(Some(Box::new(c.head.get_type()))),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Value> {
+        let mut rest = self;
+        std::iter::from_fn(move || match rest {
+            List::Nil => None,
+            List::Cons(c) => {
+                rest = &c.tail;
+                Some(c.head.as_ref())
+            }
+        })
+    }
+
     pub fn length(&self) -> usize {
         match self {
             List::Nil => 0,
@@ -0,0 +1,254 @@
+use std::{cmp::Ordering, rc::Rc};
+
+use crate::{
+    interpreter::value::Value,
+    parser::ast::Type,
+    utils::{
+        lang_error::{Error, ErrorType},
+        position::Position,
+    },
+};
+
+/*
+* Map type
+* */
+
+// immutable AVL tree ordered by key, updates copy the path to the changed node
+// and share every other node with the previous version
+#[derive(Debug, Clone, Default)]
+pub struct Map {
+    root: Option<Rc<Node>>,
+}
+
+#[derive(Debug)]
+struct Node {
+    key: Value,
+    value: Value,
+    left: Option<Rc<Node>>,
+    right: Option<Rc<Node>>,
+    height: usize,
+    size: usize,
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Map { root: None }
+    }
+
+    pub fn length(&self) -> usize {
+        size(&self.root)
+    }
+
+    // keys must be ordered (numbers, chars, strings, or lists and tuples of them)
+    // and have the type of the keys already in the map
+    pub fn check_key(&self, key: &Value, pos: &Position) -> Result<(), Error> {
+        let comparable = |other: &Value| key.compare(other).is_some();
+        let message = match &self.root {
+            _ if !comparable(key) => {
+                "map keys must be numbers, chars, strings, or lists and tuples of them"
+            }
+            Some(n) if !comparable(&n.key) || !n.key.get_type().accepts(&key.get_type()) => {
+                "map keys must match"
+            }
+            _ => return Ok(()),
+        };
+        Err(Error::new(
+            ErrorType::InvalidOperand,
+            pos.clone(),
+            key.get_type().display(),
+            Some(message),
+        ))
+    }
+
+    pub fn get(&self, key: &Value) -> Option<&Value> {
+        let mut node = self.root.as_ref();
+        while let Some(n) = node {
+            node = match cmp_keys(key, &n.key) {
+                Ordering::Less => n.left.as_ref(),
+                Ordering::Greater => n.right.as_ref(),
+                Ordering::Equal => return Some(&n.value),
+            };
+        }
+        None
+    }
+
+    // a new map with the key set to the value, the key must be valid (see check_key)
+    pub fn insert(&self, key: Value, value: Value) -> Map {
+        Map {
+            root: Some(insert(&self.root, key, value)),
+        }
+    }
+
+    // a new map without the key
+    pub fn remove(&self, key: &Value) -> Map {
+        Map {
+            root: remove(&self.root, key),
+        }
+    }
+
+    // entries in ascending key order
+    pub fn iter(&self) -> impl Iterator<Item = (&Value, &Value)> {
+        let mut stack: Vec<&Node> = Vec::new();
+        let mut node = self.root.as_deref();
+        std::iter::from_fn(move || {
+            while let Some(n) = node {
+                stack.push(n);
+                node = n.left.as_deref();
+            }
+            let n = stack.pop()?;
+            node = n.right.as_deref();
+            Some((&n.key, &n.value))
+        })
+    }
+
+    // key and value types are taken from any entry (maps are homogeneous)
+    pub fn get_type(&self) -> Type {
+        match &self.root {
+            None => Type::Map(Box::new(Type::Infer), Box::new(Type::Infer)),
+            Some(n) => Type::Map(Box::new(n.key.get_type()), Box::new(n.value.get_type())),
+        }
+    }
+
+    pub fn display(&self) -> String {
+        if self.root.is_none() {
+            return "{:}".to_string();
+        }
+        let entries: Vec<String> = self
+            .iter()
+            .map(|(k, v)| format!("{}: {}", k.display(), v.display()))
+            .collect();
+        format!("{{ {} }}", entries.join(", "))
+    }
+}
+
+/*
+* Helper functions (on nodes)
+* */
+
+// keys are checked with Map::check_key before they are inserted
+fn cmp_keys(a: &Value, b: &Value) -> Ordering {
+    a.compare(b).unwrap_or(Ordering::Equal)
+}
+
+fn height(node: &Option<Rc<Node>>) -> usize {
+    node.as_ref().map_or(0, |n| n.height)
+}
+
+fn size(node: &Option<Rc<Node>>) -> usize {
+    node.as_ref().map_or(0, |n| n.size)
+}
+
+fn make(key: Value, value: Value, left: Option<Rc<Node>>, right: Option<Rc<Node>>) -> Rc<Node> {
+    Rc::new(Node {
+        height: height(&left).max(height(&right)) + 1,
+        size: size(&left) + size(&right) + 1,
+        key,
+        value,
+        left,
+        right,
+    })
+}
+
+// like make, but restores the balance if the heights of the subtrees differ by two
+fn balance(key: Value, value: Value, left: Option<Rc<Node>>, right: Option<Rc<Node>>) -> Rc<Node> {
+    let (hl, hr) = (height(&left), height(&right));
+    if hl > hr + 1 {
+        let l = left.expect("left subtree is higher");
+        if height(&l.left) >= height(&l.right) {
+            let right = make(key, value, l.right.clone(), right);
+            return make(l.key.clone(), l.value.clone(), l.left.clone(), Some(right));
+        }
+        let lr = l.right.as_ref().expect("left-right subtree is higher");
+        let new_left = make(
+            l.key.clone(),
+            l.value.clone(),
+            l.left.clone(),
+            lr.left.clone(),
+        );
+        let new_right = make(key, value, lr.right.clone(), right);
+        return make(
+            lr.key.clone(),
+            lr.value.clone(),
+            Some(new_left),
+            Some(new_right),
+        );
+    }
+    if hr > hl + 1 {
+        let r = right.expect("right subtree is higher");
+        if height(&r.right) >= height(&r.left) {
+            let left = make(key, value, left, r.left.clone());
+            return make(r.key.clone(), r.value.clone(), Some(left), r.right.clone());
+        }
+        let rl = r.left.as_ref().expect("right-left subtree is higher");
+        let new_left = make(key, value, left, rl.left.clone());
+        let new_right = make(
+            r.key.clone(),
+            r.value.clone(),
+            rl.right.clone(),
+            r.right.clone(),
+        );
+        return make(
+            rl.key.clone(),
+            rl.value.clone(),
+            Some(new_left),
+            Some(new_right),
+        );
+    }
+    make(key, value, left, right)
+}
+
+fn insert(node: &Option<Rc<Node>>, key: Value, value: Value) -> Rc<Node> {
+    let Some(n) = node else {
+        return make(key, value, None, None);
+    };
+    match cmp_keys(&key, &n.key) {
+        Ordering::Less => balance(
+            n.key.clone(),
+            n.value.clone(),
+            Some(insert(&n.left, key, value)),
+            n.right.clone(),
+        ),
+        Ordering::Greater => balance(
+            n.key.clone(),
+            n.value.clone(),
+            n.left.clone(),
+            Some(insert(&n.right, key, value)),
+        ),
+        Ordering::Equal => make(key, value, n.left.clone(), n.right.clone()),
+    }
+}
+
+fn remove(node: &Option<Rc<Node>>, key: &Value) -> Option<Rc<Node>> {
+    let n = node.as_ref()?;
+    let res = match cmp_keys(key, &n.key) {
+        Ordering::Less => balance(
+            n.key.clone(),
+            n.value.clone(),
+            remove(&n.left, key),
+            n.right.clone(),
+        ),
+        Ordering::Greater => balance(
+            n.key.clone(),
+            n.value.clone(),
+            n.left.clone(),
+            remove(&n.right, key),
+        ),
+        // replace the node with the smallest entry of its right subtree
+        Ordering::Equal => {
+            let Some((k, v)) = first(&n.right) else {
+                return n.left.clone();
+            };
+            let right = remove(&n.right, &k);
+            balance(k, v, n.left.clone(), right)
+        }
+    };
+    Some(res)
+}
+
+fn first(node: &Option<Rc<Node>>) -> Option<(Value, Value)> {
+    let mut n = node.as_ref()?;
+    while let Some(left) = &n.left {
+        n = left;
+    }
+    Some((n.key.clone(), n.value.clone()))
+}
@@ -1,39 +1,100 @@
 use crate::interpreter::{
     closure::Closure,
     list::{Cons, List},
+    map::Map,
+    namespace::Namespace,
+    record::Record,
     scope::*,
     value::Value,
+    variant::{Constructor, Variant},
 };
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use crate::{
-    interpreter::{exec_result::ExecResult, frame::Frame, scope::Scope, symbol::*},
+    interpreter::{
+        exec_result::ExecResult, frame::Frame, scope::Scope, symbol::*, value::ArithmeticError,
+    },
+    loader::Module,
     parser::ast::*,
-    utils::lang_error::{Error, ErrorType},
+    utils::{
+        lang_error::{Error, ErrorType},
+        options::Options,
+        position::Position,
+    },
 };
 
+// unwraps the value of an expression
+// a return, break or continue in progress is passed on to the enclosing function/loop
+macro_rules! value {
+    ($result:expr) => {
+        match $result {
+            ExecResult::Value(v) => v,
+            unwinding => return Ok(unwinding),
+        }
+    };
+}
+
+pub mod bigint;
 pub mod closure;
 pub mod exec_result;
 pub mod frame;
+pub mod grapheme;
 pub mod list;
+pub mod map;
+pub mod namespace;
+pub mod record;
 pub mod scope;
 pub mod stdlib;
 pub mod symbol;
 pub mod value;
+pub mod variant;
 
-/**
+/*
 * Interpreter
 * */
 
 pub fn interpret(ast: StatementList) -> Result<(), Error> {
+    interpret_with(ast, Options::default())
+}
+
+pub fn interpret_with(ast: StatementList, options: Options) -> Result<(), Error> {
     let mut interpreter = Interpreter::new(ast);
+    interpreter.options = options;
     interpreter.run_program()?;
     Ok(())
 }
 
+// runs the files of a program in the order they were loaded (see loader::load)
+pub fn interpret_modules(modules: Vec<Module>, options: Options) -> Result<(), Error> {
+    let mut interpreter = Interpreter::new(StatementList {
+        statements: Vec::new(),
+    });
+    interpreter.options = options;
+    let entry = modules.last().map(|m| m.path.clone());
+    for module in modules {
+        interpreter.run_module(module).map_err(|mut e| {
+            // like the checker, only errors in imported files name the file
+            if e.file == entry {
+                e.file = None;
+            }
+            e
+        })?;
+    }
+    Ok(())
+}
+
 pub struct Interpreter {
     pub frames: Vec<Frame>,
     pub scope: Rc<Scope>,
+    // declared structs, by module and name (see TypeKey)
+    pub structs: HashMap<TypeKey, StructDef>,
+    // declared enums, by module and name
+    pub enums: HashMap<TypeKey, EnumDef>,
+    // files that already ran, by module id
+    pub modules: HashMap<String, Namespace>,
+    // path of the file running, None for a program that was not loaded from files
+    pub file: Option<Rc<str>>,
+    pub options: Options,
 }
 
 impl Interpreter {
@@ -41,6 +102,11 @@ impl Interpreter {
         Interpreter {
             frames: vec![Frame::new(ast)],
             scope: get_stdlib_scope(),
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            modules: HashMap::new(),
+            file: None,
+            options: Options::default(),
         }
     }
 
@@ -56,6 +122,24 @@ impl Interpreter {
         Ok(())
     }
 
+    // runs a file in its own global scope and keeps its exports for the files importing it
+    fn run_module(&mut self, module: Module) -> Result<(), Error> {
+        let names = module.ast.exports();
+        let types = module.ast.exported_types();
+        self.scope = get_stdlib_scope();
+        self.frames = vec![Frame::new(module.ast)];
+        self.file = Some(Rc::from(module.path.as_str()));
+        self.run_program().map_err(|e| e.in_file(&module.path))?;
+        let mut exports = HashMap::new();
+        for name in names {
+            let symbol = self.scope.get_symbol(&name, Position { line: 0, col: 0 })?;
+            exports.insert(name, symbol.val);
+        }
+        let namespace = Namespace::new(module.path, exports, types);
+        self.modules.insert(module.id, namespace);
+        Ok(())
+    }
+
     pub fn run_frame(&mut self) -> Result<ExecResult, Error> {
         let initial_scope = self.scope.clone();
         loop {
@@ -79,6 +163,15 @@ impl Interpreter {
                     self.scope = initial_scope;
                     return Ok(ExecResult::Returned(v));
                 }
+                // case: break/continue that no loop caught
+                ExecResult::Break | ExecResult::Continue => {
+                    return Err(Error::new(
+                        ErrorType::InvalidBreakLocation,
+                        stmt.get_position().clone(),
+                        format!("{:?}", result),
+                        Some("break and continue must be inside a loop"),
+                    ));
+                }
                 // case: continue (put the frame back)
                 _ => {
                     frame.advance();
@@ -91,30 +184,161 @@ impl Interpreter {
     fn exec(&mut self, stmt: &Statement) -> Result<ExecResult, Error> {
         match stmt {
             Statement::Assignment(a) => self.interpret_assignment(a),
-            Statement::Expression(e) => self.handle_expression(&e),
+            Statement::Destructure(d) => self.interpret_destructure(d),
+            Statement::Reassignment(r) => self.interpret_reassignment(r),
+            Statement::Expression(e) => self.handle_expression(e),
             Statement::Return(r) => self.interpret_return(r),
+            Statement::Struct(s) => {
+                self.structs
+                    .insert((s.module.clone(), s.name.clone()), s.clone());
+                Ok(ExecResult::Value(Value::Unit))
+            }
+            Statement::Enum(e) => self.interpret_enum(e),
+            Statement::While(w) => self.interpret_while(w),
+            Statement::For(f) => self.interpret_for(f),
+            Statement::Break(_) => Ok(ExecResult::Break),
+            Statement::Continue(_) => Ok(ExecResult::Continue),
+            Statement::Import(i) => self.interpret_import(i),
         }
     }
 
+    fn interpret_import(&mut self, import: &Import) -> Result<ExecResult, Error> {
+        let namespace = match import.module.as_ref().and_then(|id| self.modules.get(id)) {
+            Some(n) => n.clone(),
+            None => {
+                return Err(Error::new(
+                    ErrorType::InvalidImport,
+                    import.position.clone(),
+                    &import.path,
+                    Some("imports are resolved when a program is loaded from a file"),
+                ));
+            }
+        };
+        let mut binds = Vec::new();
+        match &import.names {
+            ImportNames::Namespace(alias) => {
+                let symbol = Value::Namespace(namespace).into_symbol(import.position.clone());
+                binds.push((alias.clone(), symbol));
+            }
+            ImportNames::Selected(names) => {
+                for name in names {
+                    // importing a struct or enum only makes it nameable (see typeck)
+                    if namespace.get(&name.name).is_none() && namespace.exports_type(&name.name) {
+                        continue;
+                    }
+                    let Some(val) = namespace.get(&name.name) else {
+                        let message = format!("\"{}\" does not export it", import.path);
+                        return Err(Error::new(
+                            ErrorType::InvalidImport,
+                            name.position.clone(),
+                            &name.name,
+                            Some(&message),
+                        ));
+                    };
+                    binds.push((
+                        name.name.clone(),
+                        val.clone().into_symbol(name.position.clone()),
+                    ));
+                }
+            }
+        }
+        self.scope = self.scope.extend_many(binds);
+        Ok(ExecResult::Value(Value::Unit))
+    }
+
+    fn interpret_while(&mut self, w: &WhileLoop) -> Result<ExecResult, Error> {
+        while value!(self.handle_expression(&w.cond)?).expect_bool()? {
+            match self.exec_scoped(&w.body, Vec::new())? {
+                ExecResult::Break => break,
+                returned @ ExecResult::Returned(_) => return Ok(returned),
+                _ => (),
+            }
+        }
+        Ok(ExecResult::Value(Value::Unit))
+    }
+
+    fn interpret_for(&mut self, f: &ForLoop) -> Result<ExecResult, Error> {
+        let mut list = value!(self.handle_expression(&f.iterable)?).expect_list()?;
+        while let List::Cons(c) = list {
+            let binds = vec![(f.identifier.clone(), c.head.into_symbol(f.position.clone()))];
+            match self.exec_scoped(&f.body, binds)? {
+                ExecResult::Break => break,
+                returned @ ExecResult::Returned(_) => return Ok(returned),
+                _ => (),
+            }
+            list = *c.tail;
+        }
+        Ok(ExecResult::Value(Value::Unit))
+    }
+
+    // runs the statements in a child scope, the result is the one of the last statement
+    // stops early on return/break/continue
+    fn exec_scoped(
+        &mut self,
+        body: &StatementList,
+        binds: Vec<(String, Symbol)>,
+    ) -> Result<ExecResult, Error> {
+        let outer = self.scope.clone();
+        self.scope = outer.extend_many(binds);
+        let mut res = Ok(ExecResult::Value(Value::Unit));
+        for stmt in &body.statements {
+            res = self.exec(stmt);
+            if !matches!(res, Ok(ExecResult::Value(_))) {
+                break;
+            }
+        }
+        self.scope = outer;
+        res
+    }
+
+    fn interpret_enum(&mut self, e: &EnumDef) -> Result<ExecResult, Error> {
+        self.enums
+            .insert((e.module.clone(), e.name.clone()), e.clone());
+        // bind a value for every variant
+        // variants with a payload are constructors, e.g. Circle(1.0)
+        for variant in &e.variants {
+            let val = if variant.fields.is_empty() {
+                Value::Variant(Variant {
+                    enum_name: e.name.clone(),
+                    name: variant.name.clone(),
+                    fields: Vec::new(),
+                })
+            } else {
+                Value::Constructor(Constructor {
+                    enum_name: e.name.clone(),
+                    name: variant.name.clone(),
+                    fields: variant.fields.clone(),
+                })
+            };
+            let symbol = val.into_symbol(variant.position.clone());
+            self.scope = self
+                .scope
+                .extend(variant.name.clone(), Rc::new(RefCell::new(symbol)));
+        }
+        Ok(ExecResult::Value(Value::Unit))
+    }
+
     fn interpret_assignment(&mut self, a: &Assignment) -> Result<ExecResult, Error> {
-        if a.assignment_type == Type::Function {
+        if matches!(a.assignment_type, Type::Function(_))
+            || matches!(a.expression, Expression::FunctionExp(_))
+        {
             return self.handle_closure(a);
         }
-        let rhs = self.handle_expression(&a.expression)?.expect_value()?;
+        let rhs = value!(self.handle_expression(&a.expression)?);
+        let rhs = self.widen(&a.assignment_type, rhs, &a.position)?;
         let symbol = Symbol {
             pos: a.position.clone(),
-            ty: a.assignment_type.clone(),
+            ty: declared_type(a, &rhs),
             val: rhs,
+            mutable: a.mutable,
         };
         // validate types
-        if a.assignment_type != symbol.val.get_type() {
-            return Err(Error::new(
-                ErrorType::TypeMismatch,
-                a.position.clone(),
-                format!("{:?}", symbol.ty),
-                Some("invalid assignment type"),
-            ));
-        }
+        check_type(
+            &symbol.ty,
+            &symbol.val,
+            &a.position,
+            "invalid assignment type",
+        )?;
         // bind
         let name = a.identifier.clone();
         let cell = Rc::new(RefCell::new(symbol));
@@ -122,29 +346,118 @@ impl Interpreter {
         Ok(ExecResult::Value(Value::Unit))
     }
 
+    fn interpret_destructure(&mut self, d: &Destructure) -> Result<ExecResult, Error> {
+        let rhs = value!(self.handle_expression(&d.expression)?);
+        let items = match rhs {
+            Value::Tuple(items) if items.len() == d.targets.len() => items,
+            other => {
+                let expected = Type::Tuple(vec![Type::Infer; d.targets.len()]);
+                return Err(Error::new(
+                    ErrorType::TypeMismatch,
+                    d.position.clone(),
+                    format!(
+                        "expected {}, found {}",
+                        expected.display(),
+                        other.get_type().display()
+                    ),
+                    Some("invalid destructuring assignment"),
+                ));
+            }
+        };
+        // bind every target
+        for (target, val) in d.targets.iter().zip(items) {
+            check_type(
+                &target.target_type,
+                &val,
+                &target.position,
+                "invalid assignment type",
+            )?;
+            let symbol = Symbol {
+                pos: target.position.clone(),
+                ty: match target.target_type {
+                    Type::Infer => val.get_type(),
+                    _ => target.target_type.clone(),
+                },
+                val,
+                mutable: false,
+            };
+            let cell = Rc::new(RefCell::new(symbol));
+            self.scope = self.scope.extend(target.identifier.clone(), cell);
+        }
+        Ok(ExecResult::Value(Value::Unit))
+    }
+
+    // updates the existing cell, so closures sharing it see the new value
+    fn interpret_reassignment(&mut self, r: &Reassignment) -> Result<ExecResult, Error> {
+        let rhs = value!(self.handle_expression(&r.expression)?);
+        let cell = self
+            .scope
+            .get_symbol_cell(&r.identifier, r.position.clone())?;
+        let mut symbol = cell.borrow_mut();
+        if !symbol.mutable {
+            return Err(Error::new(
+                ErrorType::ImmutableAssignment,
+                r.position.clone(),
+                &r.identifier,
+                Some("cannot assign twice to an immutable binding"),
+            ));
+        }
+        let rhs = self.widen(&symbol.ty, rhs, &r.position)?;
+        check_type(&symbol.ty, &rhs, &r.position, "invalid assignment type")?;
+        symbol.val = rhs;
+        Ok(ExecResult::Value(Value::Unit))
+    }
+
+    // a number assigned to a binding of a wider numeric type is promoted to it, e.g. f64 x = 1.0
+    fn widen(&self, ty: &Type, val: Value, pos: &Position) -> Result<Value, Error> {
+        let found = val.get_type();
+        if *ty == found || !ty.is_numeric() || Type::promote(ty, &found).as_ref() != Some(ty) {
+            return Ok(val);
+        }
+        if self.options.strict {
+            return Err(Error::new(
+                ErrorType::ImplicitPromotion,
+                pos.clone(),
+                format!("{} to {}", found.display(), ty.display()),
+                Some(
+                    "values must have the declared type in strict mode, convert with to_i64, to_f64, ...",
+                ),
+            ));
+        }
+        Ok(val.promote(ty))
+    }
+
     fn interpret_return(&mut self, r: &Return) -> Result<ExecResult, Error> {
-        Ok(ExecResult::Returned(
-            self.handle_expression(&r.expression)?.expect_value()?,
-        ))
+        Ok(ExecResult::Returned(value!(
+            self.handle_expression(&r.expression)?
+        )))
     }
 
     fn handle_closure(&mut self, a: &Assignment) -> Result<ExecResult, Error> {
         let name = a.identifier.clone();
         let symbol = Symbol {
             pos: a.position.clone(),
-            ty: Type::Function,
+            ty: a.assignment_type.clone(),
             val: Value::Uninitialized,
+            mutable: a.mutable,
         };
         // bind name to cell
         let cell = Rc::new(RefCell::new(symbol));
         self.scope = self.scope.extend(name, cell.clone());
         // evaluate rhs
-        let rhs = self.handle_expression(&a.expression)?.expect_value()?;
+        let rhs = value!(self.handle_expression(&a.expression)?);
+        check_type(
+            &a.assignment_type,
+            &rhs,
+            &a.position,
+            "invalid assignment type",
+        )?;
         // update/patch the cell (enables recursion)
         *cell.borrow_mut() = Symbol {
             pos: a.position.clone(),
-            ty: Type::Function,
+            ty: declared_type(a, &rhs),
             val: rhs,
+            mutable: a.mutable,
         };
         Ok(ExecResult::Value(Value::Unit))
     }
@@ -153,68 +466,182 @@ impl Interpreter {
         match expression {
             Expression::ConsExp(exp) => self.handle_cons(exp.clone()),
             Expression::ListExp(exp) => self.handle_list_exp(exp.clone()),
+            Expression::MapExp(exp) => self.handle_map_exp(exp.clone()),
             Expression::IdentifierExp(exp) => Ok(ExecResult::Value(self.handle_identifer(exp)?)),
             Expression::CallExp(exp) => self.handle_call(exp.clone()),
             Expression::LiteralExp(exp) => self.handle_literal(exp.clone()),
             Expression::BinaryExp(exp) => self.handle_binary(exp.clone()),
             Expression::IfExp(exp) => self.handle_if(exp.clone()),
             Expression::ParenExp(exp) => self.handle_paren(exp.clone()),
+            Expression::RecordExp(exp) => self.handle_record(exp),
+            Expression::FieldExp(exp) => self.handle_field(exp),
+            Expression::MatchExp(exp) => self.handle_match(exp),
+            Expression::TupleExp(exp) => {
+                let mut items = Vec::new();
+                for item in &exp.items {
+                    items.push(value!(self.handle_expression(item)?));
+                }
+                Ok(ExecResult::Value(Value::Tuple(items)))
+            }
+            Expression::TupleIndexExp(exp) => self.handle_tuple_index(exp),
+            Expression::TryExp(exp) => self.handle_try(exp),
+            Expression::UnaryExp(exp) => self.handle_unary(exp),
+            Expression::BlockExp(block) => self.exec_scoped(&block.statements, Vec::new()),
             Expression::FunctionExp(exp) => Ok(ExecResult::Value(Value::Function(Closure {
                 node: exp.clone(),
                 env: Rc::clone(&self.scope),
+                file: self.file.clone(),
             }))),
+            // calls with placeholders are partial applications, see handle_call_function
+            Expression::Placeholder(position) => Err(Error::new(
+                ErrorType::InvalidOperand,
+                position.clone(),
+                "_",
+                Some("placeholders are only allowed as call arguments"),
+            )),
+        }
+    }
+
+    fn handle_unary(&mut self, exp: &UnaryExp) -> Result<ExecResult, Error> {
+        let val = value!(self.handle_expression(&exp.operand)?);
+        if exp.operator == Operator::Not {
+            return Ok(ExecResult::Value(Value::Bool(!val.expect_bool()?)));
         }
+        let found = format!("{:?} {}", exp.operator, val.get_type().display());
+        let res = val
+            .negate(self.options.overflow)
+            .map_err(|e| arithmetic_error(e, &exp.position, found))?;
+        Ok(ExecResult::Value(res))
     }
 
     fn handle_binary(&mut self, exp: BinaryExp) -> Result<ExecResult, Error> {
-        let left_val = self.handle_expression(&exp.left)?.expect_value()?;
-        let right_val = self.handle_expression(&exp.right)?.expect_value()?;
+        let left_val = value!(self.handle_expression(&exp.left)?);
+        // boolean operators short-circuit: the right side only runs if needed
+        if matches!(exp.operator, Operator::And | Operator::Or) {
+            let left = left_val.expect_bool()?;
+            if left == (exp.operator == Operator::Or) {
+                return Ok(ExecResult::Value(Value::Bool(left)));
+            }
+            let right = value!(self.handle_expression(&exp.right)?).expect_bool()?;
+            return Ok(ExecResult::Value(Value::Bool(right)));
+        }
+        let right_val = value!(self.handle_expression(&exp.right)?);
         let left_type = left_val.get_type();
         let right_type = right_val.get_type();
+        let composes =
+            matches!(left_type, Type::Function(_)) || matches!(right_type, Type::Function(_));
+        if matches!(exp.operator, Operator::Shl | Operator::Shr) && composes {
+            return compose(&exp, left_val, right_val);
+        }
         let position = exp.position;
 
-        let arithmetic_operand_error = Error::new(
-            ErrorType::InvalidOperand,
-            position,
-            format!("{:?} {:?} {:?}", left_type, exp.operator, right_type),
-            Some("operand types must match"),
+        // operands of two different numeric types are promoted to their common type
+        // (arithmetic, comparison and equality only)
+        let promotes = !matches!(
+            exp.operator,
+            Operator::BitAnd | Operator::BitOr | Operator::BitXor | Operator::Shl | Operator::Shr
         );
+        let (left_val, right_val) = match Type::promote(&left_type, &right_type) {
+            Some(common) if promotes && left_type != right_type => {
+                if self.options.strict {
+                    return Err(Error::new(
+                        ErrorType::ImplicitPromotion,
+                        exp.operator_position.clone(),
+                        format!(
+                            "{} {:?} {}",
+                            left_type.display(),
+                            exp.operator,
+                            right_type.display()
+                        ),
+                        Some(
+                            "operands must have the same type in strict mode, convert one with to_i64, to_f64, ...",
+                        ),
+                    ));
+                }
+                (left_val.promote(&common), right_val.promote(&common))
+            }
+            _ => (left_val, right_val),
+        };
 
         let res: Value = match exp.operator {
-            // modulo operator: (operands: i32; returns: i32)
-            Operator::Mod => Value::Int(left_val.expect_int()? % right_val.expect_int()?),
             // arithmetic operators: (operands: numeric types, must match; returns: same type)
-            Operator::Mul => (left_val * right_val).ok_or_else(|| arithmetic_operand_error)?,
-            Operator::Div => (left_val / right_val).ok_or_else(|| arithmetic_operand_error)?,
-            Operator::Add => (left_val + right_val).ok_or_else(|| arithmetic_operand_error)?,
-            Operator::Sub => (left_val - right_val).ok_or_else(|| arithmetic_operand_error)?,
-            // comparison operators: (operands: numeric types; returns: bool)
-            Operator::Le => Value::Bool(left_val.expect_numeric()? <= right_val.expect_numeric()?),
-            Operator::Ge => Value::Bool(left_val.expect_numeric()? >= right_val.expect_numeric()?),
-            Operator::Lt => Value::Bool(left_val.expect_numeric()? < right_val.expect_numeric()?),
-            Operator::Gt => Value::Bool(left_val.expect_numeric()? > right_val.expect_numeric()?),
-            // TODO: make these also valid for boolean
-            Operator::Eq => Value::Bool(left_val.expect_numeric()? == right_val.expect_numeric()?),
-            Operator::Ne => Value::Bool(left_val.expect_numeric()? != right_val.expect_numeric()?),
-            // boolean operators (operands: bool; returns: bool)
-            Operator::And => Value::Bool(left_val.expect_bool()? && right_val.expect_bool()?),
-            Operator::Or => Value::Bool(left_val.expect_bool()? || right_val.expect_bool()?),
+            // `%` needs integers, `+` also concatenates strings
+            Operator::Mod | Operator::Mul | Operator::Div | Operator::Add | Operator::Sub => {
+                let found = format!(
+                    "{} {:?} {}",
+                    left_type.display(),
+                    exp.operator,
+                    right_type.display()
+                );
+                left_val
+                    .arithmetic(&exp.operator, right_val, self.options.overflow)
+                    .map_err(|e| arithmetic_error(e, &exp.operator_position, found))?
+            }
+            // equality operators: (operands: anything but functions; returns: bool)
+            Operator::Eq | Operator::Ne => {
+                let equal = left_val.equals(&right_val).ok_or(Error::new(
+                    ErrorType::InvalidOperand,
+                    position,
+                    format!(
+                        "{} {:?} {}",
+                        left_type.display(),
+                        exp.operator,
+                        right_type.display()
+                    ),
+                    Some("functions cannot be compared"),
+                ))?;
+                Value::Bool(equal == (exp.operator == Operator::Eq))
+            }
+            // comparison operators: (operands: numeric types, strings, lists, tuples; returns: bool)
+            Operator::Le | Operator::Ge | Operator::Lt | Operator::Gt => {
+                let ord = left_val.compare(&right_val).ok_or(Error::new(
+                    ErrorType::InvalidOperand,
+                    position,
+                    format!(
+                        "{} {:?} {}",
+                        left_type.display(),
+                        exp.operator,
+                        right_type.display()
+                    ),
+                    Some("values cannot be ordered"),
+                ))?;
+                Value::Bool(match exp.operator {
+                    Operator::Le => ord.is_le(),
+                    Operator::Ge => ord.is_ge(),
+                    Operator::Lt => ord.is_lt(),
+                    _ => ord.is_gt(),
+                })
+            }
+            // bitwise operators: (operands: i32; returns: i32)
+            Operator::BitAnd => Value::Int(left_val.expect_int()? & right_val.expect_int()?),
+            Operator::BitOr => Value::Int(left_val.expect_int()? | right_val.expect_int()?),
+            Operator::BitXor => Value::Int(left_val.expect_int()? ^ right_val.expect_int()?),
+            Operator::Shl | Operator::Shr => {
+                let left = left_val.expect_int()?;
+                let right = right_val.expect_int()?;
+                let shifted = match u32::try_from(right) {
+                    Ok(n) if exp.operator == Operator::Shl => left.checked_shl(n),
+                    Ok(n) => left.checked_shr(n),
+                    Err(_) => None,
+                };
+                Value::Int(shifted.ok_or(Error::new(
+                    ErrorType::InvalidOperand,
+                    position,
+                    right.to_string(),
+                    Some("shift amount must be between 0 and 31"),
+                ))?)
+            }
             _ => unreachable!(),
         };
         Ok(ExecResult::Value(res))
     }
 
     fn handle_if(&mut self, exp: IfExp) -> Result<ExecResult, Error> {
-        let cond = self
-            .handle_expression(&exp.if_cond)?
-            .expect_value()?
-            .expect_bool()?;
-        let then_branch = exp.then_branch.as_ref();
-        let else_branch = exp.else_branch.as_ref();
-        if cond {
-            self.exec(then_branch)
-        } else {
-            else_branch.map_or(Ok(ExecResult::Value(Value::Unit)), |exp| self.exec(exp))
+        let cond = value!(self.handle_expression(&exp.if_cond)?).expect_bool()?;
+        match (cond, &exp.else_branch) {
+            (true, _) => self.exec_scoped(&exp.then_branch.statements, Vec::new()),
+            (false, Some(branch)) => self.exec_scoped(&branch.statements, Vec::new()),
+            (false, None) => Ok(ExecResult::Value(Value::Unit)),
         }
     }
 
@@ -226,8 +653,11 @@ impl Interpreter {
         Ok(match lit.value {
             LiteralValue::Int(x) => ExecResult::Value(Value::Int(x)),
             LiteralValue::Float(x) => ExecResult::Value(Value::Float(x)),
+            LiteralValue::Int64(x) => ExecResult::Value(Value::Int64(x)),
+            LiteralValue::Float64(x) => ExecResult::Value(Value::Float64(x)),
             LiteralValue::String(x) => ExecResult::Value(Value::String(x)),
             LiteralValue::Bool(x) => ExecResult::Value(Value::Bool(x)),
+            LiteralValue::Char(x) => ExecResult::Value(Value::Char(x)),
             LiteralValue::Unit => ExecResult::Value(Value::Unit),
         })
     }
@@ -235,29 +665,102 @@ impl Interpreter {
     fn handle_list_exp(&mut self, lexp: ListExp) -> Result<ExecResult, Error> {
         let mut res = List::Nil;
         for item in lexp.items.iter().rev() {
-            let val = self.handle_expression(&item)?.expect_value()?;
+            let val = value!(self.handle_expression(item)?);
+            check_element(&res, &val, item.get_position())?;
             let length = res.length() + 1;
             res = List::Cons(Cons {
                 head: Box::new(val),
                 tail: Box::new(res),
-                length: length,
+                length,
             });
         }
         Ok(ExecResult::Value(Value::List(res)))
     }
 
+    fn handle_map_exp(&mut self, mexp: MapExp) -> Result<ExecResult, Error> {
+        let mut res = Map::new();
+        for (key, value) in &mexp.entries {
+            let key_val = value!(self.handle_expression(key)?);
+            let value_val = value!(self.handle_expression(value)?);
+            res.check_key(&key_val, key.get_position())?;
+            if let Type::Map(_, expected) = res.get_type() {
+                check_type(
+                    &expected,
+                    &value_val,
+                    value.get_position(),
+                    "map values must match",
+                )?;
+            }
+            res = res.insert(key_val, value_val);
+        }
+        Ok(ExecResult::Value(Value::Map(res)))
+    }
+
     fn handle_call(&mut self, call: Call) -> Result<ExecResult, Error> {
-        let callee = self
-            .handle_expression(call.callee.as_ref())?
-            .expect_value()?;
+        let callee = value!(self.handle_expression(call.callee.as_ref())?);
         match callee.get_type() {
-            Type::Function => self.handle_call_function(callee, call),
+            Type::Function(_) => self.handle_call_function(callee, call),
             _ => Ok(ExecResult::Value(Value::Unit)),
         }
     }
 
     fn handle_call_function(&mut self, value: Value, call: Call) -> Result<ExecResult, Error> {
+        let has_placeholder = call
+            .args
+            .iter()
+            .any(|a| matches!(a.value, Expression::Placeholder(_)));
+        let named = call.args.iter().find_map(|a| a.name.as_ref());
+        if let Some(name) = named
+            && !matches!(value, Value::Function(_))
+        {
+            return Err(Error::new(
+                ErrorType::InvalidParams,
+                call.position.clone(),
+                name,
+                Some("only functions declared with fn take named arguments"),
+            ));
+        }
+        // a call with placeholders or missing required arguments is a partial application
+        let partial = |required: usize, arity: usize| {
+            named.is_none()
+                && call.args.len() <= arity
+                && (has_placeholder || call.args.len() < required)
+        };
         match value {
+            Value::Function(x) if partial(x.node.required(), x.node.params.len()) => {
+                // params with a default are left out unless an argument is supplied for them
+                let supplied = x.node.required().max(call.args.len());
+                let params = x.node.params[..supplied].to_vec();
+                let returns = x.node.returns.clone();
+                self.partial_call(Value::Function(x), params, returns, call)
+            }
+            Value::NativeFunction(f) if partial(f.arity, usize::MAX) => {
+                // variadic natives (arity 0) take as many params as there are arguments
+                let params = (0..f.arity.max(call.args.len()))
+                    .map(|i| Param {
+                        position: call.position.clone(),
+                        param_type: Type::Infer,
+                        identifier: format!("${i}"),
+                        default: None,
+                    })
+                    .collect();
+                self.partial_call(Value::NativeFunction(f), params, Type::Infer, call)
+            }
+            Value::Constructor(c) if partial(c.fields.len(), c.fields.len()) => {
+                let params = c
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, ty)| Param {
+                        position: call.position.clone(),
+                        param_type: ty.clone(),
+                        identifier: format!("${i}"),
+                        default: None,
+                    })
+                    .collect();
+                let returns = Type::Named(c.enum_name.clone());
+                self.partial_call(Value::Constructor(c), params, returns, call)
+            }
             // if the corresponding value is a function, run the closure
             Value::Function(x) => self.run_closure(x, call.args),
             // case of stdlib call
@@ -265,15 +768,59 @@ impl Interpreter {
                 // evaluate arguments first
                 let mut arg_values = Vec::new();
                 for arg in call.args {
-                    arg_values.push(self.handle_expression(&arg.value)?.expect_value()?);
+                    arg_values.push(value!(self.handle_expression(&arg.value)?));
                 }
-                f(arg_values)
+                (f.function)(arg_values)
             }
+            Value::Constructor(c) => self.run_constructor(c, call),
             // otherwise, return unit type (calling any other value, e.g. 7())
             _ => Ok(ExecResult::Value(Value::Unit)),
         }
     }
 
+    // evaluates the supplied arguments and returns a closure taking the rest,
+    // e.g. sub(_, 1) is fn(x) { sub(x, 1) } with the 1 evaluated once
+    fn partial_call(
+        &mut self,
+        callee: Value,
+        params: Vec<Param>,
+        returns: Type,
+        call: Call,
+    ) -> Result<ExecResult, Error> {
+        let position = call.position;
+        // the names cannot be written in a program, so nothing can shadow them
+        let mut binds = vec![("$callee".to_string(), callee.into_symbol(position.clone()))];
+        let mut remaining = Vec::new();
+        let mut forwarded = Vec::new();
+        for (i, param) in params.into_iter().enumerate() {
+            let name = format!("${i}");
+            match call.args.get(i).map(|a| &a.value) {
+                None | Some(Expression::Placeholder(_)) => remaining.push(Param {
+                    identifier: name.clone(),
+                    default: None,
+                    ..param
+                }),
+                Some(arg) => {
+                    let val = value!(self.handle_expression(arg)?);
+                    check_type(
+                        &param.param_type,
+                        &val,
+                        arg.get_position(),
+                        "check function call",
+                    )?;
+                    binds.push((name.clone(), val.into_symbol(position.clone())));
+                }
+            }
+            forwarded.push(name);
+        }
+        let closure = Closure {
+            node: Function::forwarding(position, "$callee", remaining, forwarded, returns),
+            env: Rc::new(Scope::new()).extend_many(binds),
+            file: None,
+        };
+        Ok(ExecResult::Value(Value::Function(closure)))
+    }
+
     fn handle_identifer(&mut self, identifier: &Identifier) -> Result<Value, Error> {
         Ok(self
             .scope
@@ -283,41 +830,90 @@ impl Interpreter {
     }
 
     fn run_closure(&mut self, closure: Closure, args: Vec<Argument>) -> Result<ExecResult, Error> {
-        let num_params = closure.node.params.len();
-        let num_args = args.len();
         let func = closure.node;
-        let position = func.position;
-        let closure_scope = closure.env;
+        let position = func.position.clone();
+        let file = closure.file;
+        // errors inside the function are in the file it was defined in
+        let in_file = |e: Error| match &file {
+            Some(f) => e.in_file(f),
+            None => e,
+        };
+        let num_params = func.params.len();
+        let num_positional = args.iter().filter(|a| a.name.is_none()).count();
         // ensure correct number of arguments are passed
-        if num_params != num_args {
-            return Err(Error::new(
+        if num_positional > num_params {
+            return Err(in_file(Error::new(
                 ErrorType::InvalidParams,
                 position,
-                format!("found: {:?}, expected {:?}", num_args, num_params),
+                format!("found: {:?}, expected {:?}", num_positional, num_params),
                 Some("incorrect number of arguments"),
-            ));
+            )));
+        }
+        // the argument of each param, positional ones first, then the named ones
+        let mut slots: Vec<Option<Value>> = vec![None; num_params];
+        for (i, arg) in args.iter().enumerate() {
+            let index = match &arg.name {
+                None => i,
+                Some(name) => {
+                    let found = func.params.iter().position(|p| p.identifier == *name);
+                    let message = match found {
+                        None => "unknown argument name",
+                        Some(index) if slots[index].is_some() => "argument given more than once",
+                        Some(index) => {
+                            slots[index] = Some(value!(self.handle_expression(&arg.value)?));
+                            continue;
+                        }
+                    };
+                    return Err(Error::new(
+                        ErrorType::InvalidParams,
+                        arg.value.get_position().clone(),
+                        name,
+                        Some(message),
+                    ));
+                }
+            };
+            slots[index] = Some(value!(self.handle_expression(&arg.value)?));
         }
+        let closure_scope = closure.env;
+        let old_file = std::mem::replace(&mut self.file, file.clone());
+        let res = self.enter_closure(func, closure_scope, slots);
+        self.file = old_file;
+        res.map_err(in_file)
+    }
+
+    // binds the arguments (or the defaults) and runs the body
+    fn enter_closure(
+        &mut self,
+        func: Function,
+        closure_scope: Rc<Scope>,
+        slots: Vec<Option<Value>>,
+    ) -> Result<ExecResult, Error> {
+        let position = func.position.clone();
         let mut binds: Vec<(String, Symbol)> = Vec::new();
-        let mut evaluated_args: Vec<Symbol> = Vec::new();
-        for arg in &args {
-            let value = self
-                .handle_expression(&arg.value)?
-                .expect_value()?
-                .into_symbol(position.clone());
-            evaluated_args.push(value);
-        }
-        for i in 0..num_args {
-            let param = &func.params[i];
-            let arg_symbol = &evaluated_args[i];
-            if arg_symbol.ty != param.param_type {
-                return Err(Error::new(
-                    ErrorType::TypeMismatch,
-                    position,
-                    "type mismatch",
-                    Some("check function call"),
-                ));
-            }
-            binds.push((param.identifier.clone(), arg_symbol.clone()));
+        for (param, slot) in func.params.iter().zip(slots) {
+            let value = match (slot, &param.default) {
+                (Some(value), _) => value,
+                // defaults are evaluated in the environment the function was defined in
+                (None, Some(default)) => {
+                    let old_scope = std::mem::replace(&mut self.scope, closure_scope.clone());
+                    let res = self.handle_expression(default);
+                    self.scope = old_scope;
+                    value!(res?)
+                }
+                (None, None) => {
+                    return Err(Error::new(
+                        ErrorType::InvalidParams,
+                        position,
+                        param.identifier.clone(),
+                        Some("missing argument"),
+                    ));
+                }
+            };
+            check_type(&param.param_type, &value, &position, "check function call")?;
+            binds.push((
+                param.identifier.clone(),
+                value.into_symbol(position.clone()),
+            ));
         }
         let old_scope = self.scope.clone();
         // switch to the closure scope
@@ -331,30 +927,400 @@ impl Interpreter {
             ExecResult::Returned(v) => ExecResult::Value(v),
             _ => unreachable!(),
         };
-        let function_returned_type = res.expect_value()?.get_type();
-        if function_returned_type != func.returns {
+        check_type(
+            &func.returns,
+            &res.expect_value()?,
+            &position,
+            "function returns wrong type",
+        )?;
+        Ok(res)
+    }
+
+    fn handle_record(&mut self, exp: &RecordExp) -> Result<ExecResult, Error> {
+        let def = match self.structs.get(&(exp.module.clone(), exp.name.clone())) {
+            Some(def) => def.clone(),
+            None => {
+                return Err(Error::new(
+                    ErrorType::InvalidSymbol,
+                    exp.position.clone(),
+                    exp.name.clone(),
+                    Some("unknown struct"),
+                ));
+            }
+        };
+        // every initializer must name a declared field, at most once
+        for (i, init) in exp.fields.iter().enumerate() {
+            let message = if !def.fields.iter().any(|f| f.identifier == init.identifier) {
+                "struct has no such field"
+            } else if exp.fields[..i]
+                .iter()
+                .any(|f| f.identifier == init.identifier)
+            {
+                "field initialized more than once"
+            } else {
+                continue;
+            };
             return Err(Error::new(
-                ErrorType::TypeMismatch,
-                position,
-                format!("{:?}", function_returned_type),
-                Some("function returns wrong type"),
+                ErrorType::InvalidField,
+                init.position.clone(),
+                init.identifier.clone(),
+                Some(message),
             ));
         }
-        Ok(res)
+        // fields are stored in declaration order
+        let mut fields = Vec::new();
+        for field in &def.fields {
+            let init = match exp.fields.iter().find(|f| f.identifier == field.identifier) {
+                Some(init) => init,
+                None => {
+                    return Err(Error::new(
+                        ErrorType::InvalidField,
+                        exp.position.clone(),
+                        field.identifier.clone(),
+                        Some("missing field in record literal"),
+                    ));
+                }
+            };
+            let val = value!(self.handle_expression(&init.value)?);
+            check_type(
+                &field.field_type,
+                &val,
+                &init.position,
+                "invalid field type",
+            )?;
+            fields.push((field.identifier.clone(), val));
+        }
+        Ok(ExecResult::Value(Value::Record(Record {
+            name: def.name,
+            fields,
+        })))
+    }
+
+    fn handle_tuple_index(&mut self, exp: &TupleIndexExp) -> Result<ExecResult, Error> {
+        match value!(self.handle_expression(&exp.tuple)?) {
+            Value::Tuple(items) if exp.index < items.len() => {
+                Ok(ExecResult::Value(items[exp.index].clone()))
+            }
+            Value::Tuple(_) => Err(Error::new(
+                ErrorType::InvalidField,
+                exp.position.clone(),
+                exp.index.to_string(),
+                Some("tuple index out of range"),
+            )),
+            other => Err(Error::new(
+                ErrorType::InvalidOperand,
+                exp.position.clone(),
+                other.get_type().display(),
+                Some("positional access on a value that is not a tuple"),
+            )),
+        }
+    }
+
+    // unwraps some/ok, none/err are returned from the enclosing function
+    fn handle_try(&mut self, exp: &TryExp) -> Result<ExecResult, Error> {
+        match value!(self.handle_expression(&exp.expression)?) {
+            Value::Option(Some(v)) | Value::Result(Ok(v)) => Ok(ExecResult::Value(*v)),
+            val @ (Value::Option(None) | Value::Result(Err(_))) => Ok(ExecResult::Returned(val)),
+            other => Err(Error::new(
+                ErrorType::InvalidOperand,
+                exp.position.clone(),
+                other.get_type().display(),
+                Some("`?` expects an option or a result"),
+            )),
+        }
+    }
+
+    fn handle_field(&mut self, exp: &FieldExp) -> Result<ExecResult, Error> {
+        let record = match value!(self.handle_expression(&exp.record)?) {
+            Value::Record(r) => r,
+            // qualified access, e.g. lib.map
+            Value::Namespace(n) => {
+                return match n.get(&exp.field) {
+                    Some(val) => Ok(ExecResult::Value(val.clone())),
+                    None => Err(Error::new(
+                        ErrorType::InvalidSymbol,
+                        exp.position.clone(),
+                        exp.field.clone(),
+                        Some("the module does not export it"),
+                    )),
+                };
+            }
+            other => {
+                return Err(Error::new(
+                    ErrorType::InvalidOperand,
+                    exp.position.clone(),
+                    other.get_type().display(),
+                    Some("field access on a value that is not a record"),
+                ));
+            }
+        };
+        match record.get(&exp.field) {
+            Some(val) => Ok(ExecResult::Value(val.clone())),
+            None => Err(Error::new(
+                ErrorType::InvalidField,
+                exp.position.clone(),
+                exp.field.clone(),
+                Some("record has no such field"),
+            )),
+        }
+    }
+
+    fn run_constructor(&mut self, c: Constructor, call: Call) -> Result<ExecResult, Error> {
+        if c.fields.len() != call.args.len() {
+            return Err(Error::new(
+                ErrorType::InvalidParams,
+                call.position,
+                format!(
+                    "found: {:?}, expected {:?}",
+                    call.args.len(),
+                    c.fields.len()
+                ),
+                Some("incorrect number of arguments"),
+            ));
+        }
+        let mut fields = Vec::new();
+        for (ty, arg) in c.fields.iter().zip(&call.args) {
+            let val = value!(self.handle_expression(&arg.value)?);
+            check_type(
+                ty,
+                &val,
+                arg.value.get_position(),
+                "invalid variant payload",
+            )?;
+            fields.push(val);
+        }
+        Ok(ExecResult::Value(Value::Variant(Variant {
+            enum_name: c.enum_name,
+            name: c.name,
+            fields,
+        })))
+    }
+
+    fn handle_match(&mut self, exp: &MatchExp) -> Result<ExecResult, Error> {
+        let subject = value!(self.handle_expression(&exp.subject)?);
+        for arm in &exp.arms {
+            let mut binds = Vec::new();
+            if !self.match_pattern(&arm.pattern, &subject, &mut binds) {
+                continue;
+            }
+            // the arm runs in a new scope with the bound payloads
+            let old_scope = self.scope.clone();
+            self.scope = self.scope.extend_many(binds);
+            if let Some(guard) = &arm.guard {
+                let passed = value!(self.handle_expression(guard)?);
+                if !passed.expect_bool()? {
+                    self.scope = old_scope;
+                    continue;
+                }
+            }
+            let res = self.exec_scoped(&arm.body.statements, Vec::new());
+            self.scope = old_scope;
+            return res;
+        }
+        Err(Error::new(
+            ErrorType::NonExhaustiveMatch,
+            exp.position.clone(),
+            subject.display(),
+            Some("no match arm matches the value"),
+        ))
+    }
+
+    // returns true if the value matches, collecting the bindings
+    fn match_pattern(
+        &self,
+        pattern: &Pattern,
+        val: &Value,
+        binds: &mut Vec<(String, Symbol)>,
+    ) -> bool {
+        match pattern {
+            Pattern::Wildcard(_) => true,
+            Pattern::Identifier(id) if id.name == "none" => matches!(val, Value::Option(None)),
+            Pattern::Identifier(id) if self.is_unit_variant(&id.name) => {
+                matches!(val, Value::Variant(v) if v.name == id.name)
+            }
+            Pattern::Identifier(id) => {
+                binds.push((
+                    id.name.clone(),
+                    val.clone().into_symbol(id.position.clone()),
+                ));
+                true
+            }
+            Pattern::Literal(lit) => match (&lit.value, val) {
+                (LiteralValue::Int(a), Value::Int(b)) => a == b,
+                (LiteralValue::Float(a), Value::Float(b)) => a == b,
+                (LiteralValue::Int64(a), Value::Int64(b)) => a == b,
+                (LiteralValue::Float64(a), Value::Float64(b)) => a == b,
+                (LiteralValue::Bool(a), Value::Bool(b)) => a == b,
+                (LiteralValue::Char(a), Value::Char(b)) => a == b,
+                (LiteralValue::String(a), Value::String(b)) => a == b,
+                (LiteralValue::Unit, Value::Unit) => true,
+                _ => false,
+            },
+            Pattern::Variant(p) if p.fields.len() == 1 && is_builtin_variant(&p.name) => {
+                match (p.name.as_str(), val) {
+                    ("some", Value::Option(Some(v)))
+                    | ("ok", Value::Result(Ok(v)))
+                    | ("err", Value::Result(Err(v))) => self.match_pattern(&p.fields[0], v, binds),
+                    _ => false,
+                }
+            }
+            Pattern::Variant(p) => match val {
+                Value::Variant(v) if v.name == p.name && v.fields.len() == p.fields.len() => p
+                    .fields
+                    .iter()
+                    .zip(&v.fields)
+                    .all(|(p, v)| self.match_pattern(p, v, binds)),
+                _ => false,
+            },
+            Pattern::Tuple(p) => match val {
+                Value::Tuple(items) if items.len() == p.items.len() => p
+                    .items
+                    .iter()
+                    .zip(items)
+                    .all(|(p, v)| self.match_pattern(p, v, binds)),
+                _ => false,
+            },
+            Pattern::List(p) => match val {
+                Value::List(list) => self.match_items(&p.items, list, binds),
+                _ => false,
+            },
+            Pattern::Cons(p) => match val {
+                Value::List(List::Cons(c)) => {
+                    self.match_pattern(&p.head, &c.head, binds)
+                        && self.match_pattern(&p.tail, &Value::List(*c.tail.clone()), binds)
+                }
+                _ => false,
+            },
+        }
+    }
+
+    // matches the list element by element, the lengths must be equal
+    fn match_items(
+        &self,
+        items: &[Pattern],
+        list: &List,
+        binds: &mut Vec<(String, Symbol)>,
+    ) -> bool {
+        match (items.split_first(), list) {
+            (None, List::Nil) => true,
+            (Some((first, rest)), List::Cons(c)) => {
+                self.match_pattern(first, &c.head, binds) && self.match_items(rest, &c.tail, binds)
+            }
+            _ => false,
+        }
+    }
+
+    // returns true if the name refers to a declared variant without payload
+    // the checker turns the ones it resolves into variant patterns (see typeck::check_pattern)
+    // so only programs that were not checked get here, and they are a single file
+    fn is_unit_variant(&self, name: &str) -> bool {
+        self.enums
+            .iter()
+            .filter(|((module, _), _)| module.is_none())
+            .flat_map(|(_, e)| &e.variants)
+            .any(|v| v.name == name && v.fields.is_empty())
     }
 
     fn handle_cons(&mut self, exp: ConsExp) -> Result<ExecResult, Error> {
-        let head = self.handle_expression(exp.head.as_ref())?.expect_value()?;
-        let tail = self
-            .handle_expression(exp.tail.as_ref())?
-            .expect_value()?
-            .expect_list()?;
+        let head = value!(self.handle_expression(exp.head.as_ref())?);
+        let tail = value!(self.handle_expression(exp.tail.as_ref())?).expect_list()?;
+        check_element(&tail, &head, &exp.position)?;
         let length = tail.length() + 1;
         let res = List::Cons(Cons {
             head: Box::new(head),
             tail: Box::new(tail),
-            length: length,
+            length,
         });
         Ok(ExecResult::Value(Value::List(res)))
     }
 }
+
+/*
+* Helper functions
+* */
+
+// turns a failed arithmetic operation into an error at the given position
+fn arithmetic_error(error: ArithmeticError, position: &Position, found: String) -> Error {
+    let (error_type, message) = match error {
+        ArithmeticError::InvalidOperands => (ErrorType::InvalidOperand, "operand types must match"),
+        ArithmeticError::DivisionByZero => (ErrorType::DivisionByZero, "cannot divide by zero"),
+        ArithmeticError::Overflow => (
+            ErrorType::IntegerOverflow,
+            "result does not fit in its type, see --overflow in `lang help`",
+        ),
+    };
+    Error::new(error_type, position.clone(), found, Some(message))
+}
+
+// ensures a value can be used where the type is expected
+fn check_type(expected: &Type, val: &Value, pos: &Position, message: &str) -> Result<(), Error> {
+    let found = match (expected, val) {
+        // params with a default can be left out, e.g. fn(i32, i32 = 1) fits fn(i32)
+        (Type::Function(Some(f)), Value::Function(c))
+            if (c.node.required()..c.node.params.len()).contains(&f.params.len()) =>
+        {
+            c.node.get_type_with(f.params.len())
+        }
+        _ => val.get_type(),
+    };
+    if expected.accepts(&found) {
+        return Ok(());
+    }
+    Err(Error::new(
+        ErrorType::TypeMismatch,
+        pos.clone(),
+        format!("expected {}, found {}", expected.display(), found.display()),
+        Some(message),
+    ))
+}
+
+// the type a binding is declared with (inferred bindings use the value)
+fn declared_type(a: &Assignment, val: &Value) -> Type {
+    match a.assignment_type {
+        Type::Infer => val.get_type(),
+        _ => a.assignment_type.clone(),
+    }
+}
+
+// ensures a value can be added to a list (lists are homogeneous)
+fn check_element(list: &List, val: &Value, pos: &Position) -> Result<(), Error> {
+    match list.get_type() {
+        Type::List(Some(element)) => check_type(&element, val, pos, "list elements must match"),
+        _ => Ok(()),
+    }
+}
+
+// `f >> g` (or `g << f`) is a closure applying f, then g to the result
+fn compose(exp: &BinaryExp, left: Value, right: Value) -> Result<ExecResult, Error> {
+    let (first, second) = match exp.operator {
+        Operator::Shr => (left, right),
+        _ => (right, left),
+    };
+    for f in [&first, &second] {
+        if !matches!(f.get_type(), Type::Function(_)) {
+            return Err(Error::new(
+                ErrorType::InvalidOperand,
+                exp.operator_position.clone(),
+                f.get_type().display(),
+                Some("only functions can be composed"),
+            ));
+        }
+    }
+    // the names cannot be written in a program, so nothing can shadow them
+    let binds = vec![
+        (
+            "$first".to_string(),
+            first.into_symbol(exp.position.clone()),
+        ),
+        (
+            "$second".to_string(),
+            second.into_symbol(exp.position.clone()),
+        ),
+    ];
+    let closure = Closure {
+        node: Function::composition(exp.position.clone(), "$first", "$second"),
+        env: Rc::new(Scope::new()).extend_many(binds),
+        file: None,
+    };
+    Ok(ExecResult::Value(Value::Function(closure)))
+}
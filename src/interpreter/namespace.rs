@@ -0,0 +1,38 @@
+use crate::interpreter::value::Value;
+use std::{collections::HashMap, rc::Rc};
+
+/*
+* Namespace type
+* */
+
+// the exports of an imported file, e.g. `lib` in import "lib.lang" as lib
+// shared by every file importing it, the file itself runs only once
+#[derive(Debug, Clone)]
+pub struct Namespace {
+    pub path: String,
+    exports: Rc<HashMap<String, Value>>,
+    // exported structs and enums, they have no value but can be imported by name
+    types: Rc<Vec<String>>,
+}
+
+impl Namespace {
+    pub fn new(path: String, exports: HashMap<String, Value>, types: Vec<String>) -> Self {
+        Namespace {
+            path,
+            exports: Rc::new(exports),
+            types: Rc::new(types),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.exports.get(name)
+    }
+
+    pub fn exports_type(&self, name: &str) -> bool {
+        self.types.iter().any(|t| t == name)
+    }
+
+    pub fn display(&self) -> String {
+        format!("[module {}]", self.path)
+    }
+}
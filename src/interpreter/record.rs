@@ -0,0 +1,38 @@
+use crate::{interpreter::value::Value, parser::ast::Type};
+
+/*
+* Record type
+* */
+
+#[derive(Debug, Clone)]
+pub struct Record {
+    // name of the struct the record was built from
+    pub name: String,
+    // fields in declaration order
+    pub fields: Vec<(String, Value)>,
+}
+
+impl Record {
+    pub fn get(&self, field: &str) -> Option<&Value> {
+        self.fields
+            .iter()
+            .find(|(name, _)| name == field)
+            .map(|(_, val)| val)
+    }
+
+    pub fn get_type(&self) -> Type {
+        Type::Named(self.name.clone())
+    }
+
+    pub fn display(&self) -> String {
+        if self.fields.is_empty() {
+            return format!("{} {{}}", self.name);
+        }
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(name, val)| format!("{}: {}", name, val.display()))
+            .collect();
+        format!("{} {{ {} }}", self.name, fields.join(", "))
+    }
+}
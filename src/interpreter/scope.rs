@@ -1,10 +1,15 @@
 use crate::{
-    interpreter::{stdlib::*, symbol::Symbol, value::Value},
+    interpreter::{
+        closure::{Native, NativeFn},
+        stdlib::*,
+        symbol::Symbol,
+        value::Value,
+    },
     parser::ast::Type,
     utils::lang_error::{Error, ErrorType},
     utils::position::Position,
 };
-use std::{cell::RefCell, collections::HashMap, iter::zip, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 /**
 * Scope type
@@ -22,32 +27,67 @@ pub fn get_stdlib_scope() -> Rc<Scope> {
         parent: None,
     };
     let pos = Position { col: 0, line: 0 };
+    // name, function, number of params (calls with fewer arguments are partial applications)
     // TODO: this could probably be a macro?
-    let names = vec![
-        "floor", "print", "println", "panic", "read", "assert", "head", "tail", "length",
+    let natives: Vec<(&str, NativeFn, usize)> = vec![
+        ("floor", std_floor, 1),
+        ("print", std_print, 0),
+        ("println", std_println, 0),
+        ("panic", std_panic, 0),
+        ("read", std_read, 0),
+        ("assert", std_assert, 1),
+        ("head", std_head, 1),
+        ("tail", std_tail, 1),
+        ("length", std_length, 1),
+        ("some", std_some, 1),
+        ("ok", std_ok, 1),
+        ("err", std_err, 1),
+        ("try_head", std_try_head, 1),
+        ("try_tail", std_try_tail, 1),
+        ("try_read", std_try_read, 0),
+        ("to_string", std_to_string, 1),
+        ("to_i32", std_to_i32, 1),
+        ("to_i64", std_to_i64, 1),
+        ("to_f32", std_to_f32, 1),
+        ("to_f64", std_to_f64, 1),
+        ("to_bigint", std_to_bigint, 1),
+        ("chars", std_chars, 1),
+        ("from_chars", std_from_chars, 1),
+        ("code_points", std_code_points, 1),
+        ("from_code_points", std_from_code_points, 1),
+        ("graphemes", std_graphemes, 1),
+        ("grapheme_at", std_grapheme_at, 2),
+        ("try_grapheme_at", std_try_grapheme_at, 2),
+        ("get", std_get, 2),
+        ("insert", std_insert, 3),
+        ("remove", std_remove, 2),
+        ("keys", std_keys, 1),
+        ("values", std_values, 1),
+        ("contains_key", std_contains_key, 2),
     ];
-    let functions = vec![
-        std_floor,
-        std_print,
-        std_println,
-        std_panic,
-        std_read,
-        std_assert,
-        std_head,
-        std_tail,
-        std_length,
-    ];
-    for (name, function) in zip(names, functions) {
+    for (name, function, arity) in natives {
         let symbol = Symbol {
             pos: pos.clone(),
-            ty: Type::Function,
-            val: Value::NativeFunction(function),
+            ty: Type::Function(None),
+            val: Value::NativeFunction(Native { function, arity }),
+            mutable: false,
         };
         new_scope
             .symbols
             .insert(name.to_string(), Rc::new(RefCell::new(symbol)));
     }
-    return Rc::new(new_scope);
+    // `none` is a plain value rather than a function
+    let none = Value::Option(None).into_symbol(pos);
+    new_scope
+        .symbols
+        .insert("none".to_string(), Rc::new(RefCell::new(none)));
+    Rc::new(new_scope)
+}
+
+impl Default for Scope {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Scope {
@@ -1,12 +1,15 @@
 use std::io::{self, Write};
 
 use crate::{
-    interpreter::{exec_result::ExecResult, list::List, value::Value},
+    interpreter::{
+        bigint::BigInt, exec_result::ExecResult, grapheme::graphemes, list::List, map::Map,
+        value::Value,
+    },
     utils::lang_error::{Error, ErrorType},
     utils::position::Position,
 };
 
-/**
+/*
 * Standard library functions
 * */
 
@@ -42,6 +45,103 @@ pub fn std_floor(args: Vec<Value>) -> Result<ExecResult, Error> {
     )))
 }
 
+pub fn std_to_string(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let val = get_arg(&args, 0)?;
+    Ok(ExecResult::Value(Value::String(val.display())))
+}
+
+// numeric conversions, integers that do not fit are an error and floats are truncated
+pub fn std_to_i32(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let val = to_integer(get_arg(&args, 0)?, "i32")?;
+    let res = val
+        .to_i64()
+        .and_then(|x| i32::try_from(x).ok())
+        .ok_or_else(|| conversion_error(&val.display(), "i32"))?;
+    Ok(ExecResult::Value(Value::Int(res)))
+}
+
+pub fn std_to_i64(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let val = to_integer(get_arg(&args, 0)?, "i64")?;
+    let res = val
+        .to_i64()
+        .ok_or_else(|| conversion_error(&val.display(), "i64"))?;
+    Ok(ExecResult::Value(Value::Int64(res)))
+}
+
+pub fn std_to_bigint(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let val = to_integer(get_arg(&args, 0)?, "bigint")?;
+    Ok(ExecResult::Value(Value::BigInt(val)))
+}
+
+pub fn std_to_f32(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let val = get_arg(&args, 0)?.expect_numeric()?;
+    Ok(ExecResult::Value(Value::Float(val as f32)))
+}
+
+pub fn std_to_f64(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let val = get_arg(&args, 0)?.expect_numeric()?;
+    Ok(ExecResult::Value(Value::Float64(val)))
+}
+
+// strings as chars (unicode scalar values), code points or graphemes (user-perceived characters)
+pub fn std_chars(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let s = get_arg(&args, 0)?.expect_string()?;
+    let chars = s.chars().map(Value::Char).collect();
+    Ok(ExecResult::Value(Value::List(List::from_values(chars))))
+}
+
+pub fn std_from_chars(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let list = get_arg(&args, 0)?.expect_list()?;
+    let s = list
+        .iter()
+        .map(|c| c.expect_char())
+        .collect::<Result<String, Error>>()?;
+    Ok(ExecResult::Value(Value::String(s)))
+}
+
+pub fn std_code_points(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let s = get_arg(&args, 0)?.expect_string()?;
+    let code_points = s.chars().map(|c| Value::Int(c as i32)).collect();
+    Ok(ExecResult::Value(Value::List(List::from_values(
+        code_points,
+    ))))
+}
+
+pub fn std_from_code_points(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let list = get_arg(&args, 0)?.expect_list()?;
+    let s = list
+        .iter()
+        .map(|x| {
+            let x = x.expect_int()?;
+            u32::try_from(x)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or_else(|| conversion_error(&x.to_string(), "char"))
+        })
+        .collect::<Result<String, Error>>()?;
+    Ok(ExecResult::Value(Value::String(s)))
+}
+
+pub fn std_graphemes(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let s = get_arg(&args, 0)?.expect_string()?;
+    let graphemes = graphemes(&s)
+        .into_iter()
+        .map(|g| Value::String(g.to_string()))
+        .collect();
+    Ok(ExecResult::Value(Value::List(List::from_values(graphemes))))
+}
+
+pub fn std_grapheme_at(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let (s, index) = (get_arg(&args, 0)?, get_arg(&args, 1)?.expect_int()?);
+    let grapheme = grapheme_at(&s.expect_string()?, index).ok_or(Error::new(
+        ErrorType::IndexOutOfBounds,
+        POSITION,
+        index.to_string(),
+        Some(&format!("{:?} has no grapheme at this index", s.display())),
+    ))?;
+    Ok(ExecResult::Value(Value::String(grapheme)))
+}
+
 pub fn std_length(args: Vec<Value>) -> Result<ExecResult, Error> {
     let list = get_arg(&args, 0)?.expect_list()?;
     Ok(ExecResult::Value(Value::Int(list.length() as i32)))
@@ -50,14 +150,12 @@ pub fn std_length(args: Vec<Value>) -> Result<ExecResult, Error> {
 pub fn std_head(args: Vec<Value>) -> Result<ExecResult, Error> {
     let list = get_arg(&args, 0)?.expect_list()?.clone();
     match list {
-        List::Nil => {
-            return Err(Error::new(
-                ErrorType::EmptyList,
-                POSITION,
-                "expected head to exist",
-                None,
-            ));
-        }
+        List::Nil => Err(Error::new(
+            ErrorType::EmptyList,
+            POSITION,
+            "expected head to exist",
+            None,
+        )),
         List::Cons(c) => Ok(ExecResult::Value(c.head.as_ref().clone())),
     }
 }
@@ -65,23 +163,122 @@ pub fn std_head(args: Vec<Value>) -> Result<ExecResult, Error> {
 pub fn std_tail(args: Vec<Value>) -> Result<ExecResult, Error> {
     let list = get_arg(&args, 0)?.expect_list()?.clone();
     match list {
-        List::Nil => {
-            return Err(Error::new(
-                ErrorType::EmptyList,
-                POSITION,
-                "expected head to exist",
-                None,
-            ));
-        }
+        List::Nil => Err(Error::new(
+            ErrorType::EmptyList,
+            POSITION,
+            "expected head to exist",
+            None,
+        )),
         List::Cons(c) => Ok(ExecResult::Value(Value::List(c.tail.as_ref().clone()))),
     }
 }
 
+// maps are persistent, insert and remove return a new map and leave the old one unchanged
+pub fn std_get(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let (map, key) = map_and_key(&args)?;
+    let val = map.get(key).map(|v| Box::new(v.clone()));
+    Ok(ExecResult::Value(Value::Option(val)))
+}
+
+pub fn std_contains_key(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let (map, key) = map_and_key(&args)?;
+    Ok(ExecResult::Value(Value::Bool(map.get(key).is_some())))
+}
+
+pub fn std_insert(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let (map, key) = map_and_key(&args)?;
+    let val = get_arg(&args, 2)?;
+    if let Some((_, other)) = map.iter().next()
+        && !other.get_type().accepts(&val.get_type())
+    {
+        return Err(Error::new(
+            ErrorType::TypeMismatch,
+            POSITION,
+            format!(
+                "expected {}, found {}",
+                other.get_type().display(),
+                val.get_type().display()
+            ),
+            Some("map values must match"),
+        ));
+    }
+    let res = map.insert(key.clone(), val.clone());
+    Ok(ExecResult::Value(Value::Map(res)))
+}
+
+pub fn std_remove(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let (map, key) = map_and_key(&args)?;
+    Ok(ExecResult::Value(Value::Map(map.remove(key))))
+}
+
+// keys and values in ascending key order
+pub fn std_keys(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let map = get_arg(&args, 0)?.expect_map()?;
+    let keys = map.iter().map(|(k, _)| k.clone()).collect();
+    Ok(ExecResult::Value(Value::List(List::from_values(keys))))
+}
+
+pub fn std_values(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let map = get_arg(&args, 0)?.expect_map()?;
+    let values = map.iter().map(|(_, v)| v.clone()).collect();
+    Ok(ExecResult::Value(Value::List(List::from_values(values))))
+}
+
+pub fn std_some(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let val = get_arg(&args, 0)?.clone();
+    Ok(ExecResult::Value(Value::Option(Some(Box::new(val)))))
+}
+
+pub fn std_ok(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let val = get_arg(&args, 0)?.clone();
+    Ok(ExecResult::Value(Value::Result(Ok(Box::new(val)))))
+}
+
+pub fn std_err(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let val = get_arg(&args, 0)?.clone();
+    Ok(ExecResult::Value(Value::Result(Err(Box::new(val)))))
+}
+
+// safe variants of head, tail and read, these never fail
+pub fn std_try_head(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let list = get_arg(&args, 0)?.expect_list()?;
+    let head = match list {
+        List::Nil => None,
+        List::Cons(c) => Some(c.head.clone()),
+    };
+    Ok(ExecResult::Value(Value::Option(head)))
+}
+
+pub fn std_try_tail(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let list = get_arg(&args, 0)?.expect_list()?;
+    let tail = match list {
+        List::Nil => None,
+        List::Cons(c) => Some(Box::new(Value::List(c.tail.as_ref().clone()))),
+    };
+    Ok(ExecResult::Value(Value::Option(tail)))
+}
+
+pub fn std_try_grapheme_at(args: Vec<Value>) -> Result<ExecResult, Error> {
+    let s = get_arg(&args, 0)?.expect_string()?;
+    let grapheme = grapheme_at(&s, get_arg(&args, 1)?.expect_int()?);
+    Ok(ExecResult::Value(Value::Option(
+        grapheme.map(|g| Box::new(Value::String(g))),
+    )))
+}
+
+pub fn std_try_read(_args: Vec<Value>) -> Result<ExecResult, Error> {
+    io::stdout().flush().unwrap();
+    let mut buffer = String::new();
+    let res = match io::stdin().read_line(&mut buffer) {
+        Ok(_) => Ok(Box::new(Value::String(buffer))),
+        Err(e) => Err(Box::new(Value::String(e.to_string()))),
+    };
+    Ok(ExecResult::Value(Value::Result(res)))
+}
+
 pub fn std_assert(args: Vec<Value>) -> Result<ExecResult, Error> {
     let cond = get_arg(&args, 0)?.expect_bool()?;
-    let optional_msg = args
-        .get(1)
-        .and_then(|r| return Some(r.expect_string().unwrap()));
+    let optional_msg = args.get(1).map(|r| r.expect_string().unwrap());
     if !cond {
         return Err(Error::new(
             ErrorType::StdAssertionFailure,
@@ -97,11 +294,11 @@ pub fn std_panic(_args: Vec<Value>) -> Result<ExecResult, Error> {
     panic!("[panic]");
 }
 
-/**
+/*
 * Helper functions
 * */
 
-fn get_arg<'a>(args: &'a Vec<Value>, index: usize) -> Result<&'a Value, Error> {
+fn get_arg(args: &[Value], index: usize) -> Result<&Value, Error> {
     args.get(index).ok_or(Error::new(
         ErrorType::StdMissingArgs,
         POSITION,
@@ -109,3 +306,36 @@ fn get_arg<'a>(args: &'a Vec<Value>, index: usize) -> Result<&'a Value, Error> {
         None,
     ))
 }
+
+// integers and floats (truncated) as an exact integer, strings are parsed as decimal digits
+fn to_integer(val: &Value, target: &str) -> Result<BigInt, Error> {
+    match val {
+        Value::Float(x) => BigInt::from_f64(*x as f64),
+        Value::Float64(x) => BigInt::from_f64(*x),
+        Value::String(s) => BigInt::parse(s.trim()),
+        val => return val.expect_integer(),
+    }
+    .ok_or_else(|| conversion_error(&val.display(), target))
+}
+
+// the map and key arguments of the map functions, the key must fit the map
+fn map_and_key(args: &[Value]) -> Result<(Map, &Value), Error> {
+    let map = get_arg(args, 0)?.expect_map()?;
+    let key = get_arg(args, 1)?;
+    map.check_key(key, &POSITION)?;
+    Ok((map, key))
+}
+
+fn grapheme_at(s: &str, index: i32) -> Option<String> {
+    let index = usize::try_from(index).ok()?;
+    graphemes(s).get(index).map(|g| g.to_string())
+}
+
+fn conversion_error(found: &str, target: &str) -> Error {
+    Error::new(
+        ErrorType::InvalidOperand,
+        POSITION,
+        found,
+        Some(&format!("value cannot be converted to {}", target)),
+    )
+}
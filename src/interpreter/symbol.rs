@@ -9,4 +9,6 @@ pub struct Symbol {
     pub pos: Position,
     pub ty: Type,
     pub val: Value,
+    // only mutable symbols can be reassigned
+    pub mutable: bool,
 }
@@ -1,13 +1,23 @@
-use std::ops::{Add, Div, Mul, Sub};
+use std::cmp::Ordering;
 
 use crate::{
-    interpreter::{closure::Closure, exec_result::ExecResult, list::List, symbol::Symbol},
-    parser::ast::Type,
+    interpreter::{
+        bigint::BigInt,
+        closure::{Closure, Native},
+        list::List,
+        map::Map,
+        namespace::Namespace,
+        record::Record,
+        symbol::Symbol,
+        variant::{Constructor, Variant},
+    },
+    parser::ast::{Operator, Type},
     utils::lang_error::Error,
+    utils::options::Overflow,
     utils::position::Position,
 };
 
-/**
+/*
 * Value type
 * */
 
@@ -15,12 +25,24 @@ use crate::{
 pub enum Value {
     Int(i32),
     Float(f32),
+    Int64(i64),
+    Float64(f64),
+    BigInt(BigInt),
     Bool(bool),
+    Char(char),
     String(String),
     Function(Closure),
-    NativeFunction(fn(Vec<Value>) -> Result<ExecResult, Error>),
+    NativeFunction(Native),
     Uninitialized,
     List(List),
+    Map(Map),
+    Namespace(Namespace),
+    Record(Record),
+    Tuple(Vec<Value>),
+    Variant(Variant),
+    Constructor(Constructor),
+    Option(Option<Box<Value>>),
+    Result(Result<Box<Value>, Box<Value>>),
     Unit,
 }
 
@@ -38,17 +60,51 @@ impl Value {
         Err(Error::generic_invalid_operand(self, Some("expected f32")))
     }
 
-    pub fn expect_numeric(&self) -> Result<f32, Error> {
+    pub fn expect_numeric(&self) -> Result<f64, Error> {
         match self {
-            Value::Float(x) => Ok(*x),
-            Value::Int(x) => Ok(*x as f32),
+            Value::Int(x) => Ok(*x as f64),
+            Value::Float(x) => Ok(*x as f64),
+            Value::Int64(x) => Ok(*x as f64),
+            Value::Float64(x) => Ok(*x),
+            Value::BigInt(x) => Ok(x.to_f64()),
             _ => Err(Error::generic_invalid_operand(
                 self,
-                Some("expected numeric (i32, f32)"),
+                Some("expected numeric (i32, f32, i64, f64, bigint)"),
             )),
         }
     }
 
+    // converts a number to a wider type of the promotion lattice (see Type::promote)
+    pub fn promote(self, ty: &Type) -> Value {
+        match (self, ty) {
+            (Value::Int(x), Type::I64) => Value::Int64(x as i64),
+            (Value::Int(x), Type::F32) => Value::Float(x as f32),
+            (Value::Int(x), Type::BigInt) => Value::BigInt(BigInt::from_i64(x as i64)),
+            (Value::Int64(x), Type::BigInt) => Value::BigInt(BigInt::from_i64(x)),
+            (Value::Int(x), Type::F64) => Value::Float64(x as f64),
+            (Value::Int64(x), Type::F64) => Value::Float64(x as f64),
+            (Value::Float(x), Type::F64) => Value::Float64(x as f64),
+            (val, _) => val,
+        }
+    }
+
+    // integers of any width, exactly
+    pub fn expect_integer(&self) -> Result<BigInt, Error> {
+        match self {
+            Value::Int(x) => Ok(BigInt::from_i64(*x as i64)),
+            Value::Int64(x) => Ok(BigInt::from_i64(*x)),
+            Value::BigInt(x) => Ok(x.clone()),
+            _ => Err(Error::generic_invalid_operand(
+                self,
+                Some("expected integer (i32, i64, bigint)"),
+            )),
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        self.expect_numeric().is_ok()
+    }
+
     pub fn expect_bool(&self) -> Result<bool, Error> {
         if let Value::Bool(x) = self {
             return Ok(*x);
@@ -66,6 +122,20 @@ impl Value {
         ))
     }
 
+    pub fn expect_char(&self) -> Result<char, Error> {
+        if let Value::Char(x) = self {
+            return Ok(*x);
+        }
+        Err(Error::generic_invalid_operand(self, Some("expected char")))
+    }
+
+    pub fn expect_map(&self) -> Result<Map, Error> {
+        if let Value::Map(x) = self {
+            return Ok(x.clone());
+        }
+        Err(Error::generic_invalid_operand(self, Some("expected map")))
+    }
+
     pub fn expect_function(&self) -> Result<Closure, Error> {
         if let Value::Function(x) = self {
             return Ok(x.clone());
@@ -85,9 +155,10 @@ impl Value {
 
     pub fn into_symbol(self, pos: Position) -> Symbol {
         Symbol {
-            pos: pos,
+            pos,
             ty: self.get_type(),
             val: self,
+            mutable: false,
         }
     }
 
@@ -95,11 +166,28 @@ impl Value {
         match self {
             Value::Int(_) => Type::I32,
             Value::Float(_) => Type::F32,
+            Value::Int64(_) => Type::I64,
+            Value::Float64(_) => Type::F64,
+            Value::BigInt(_) => Type::BigInt,
             Value::Bool(_) => Type::Bool,
+            Value::Char(_) => Type::Char,
             Value::String(_) => Type::String,
-            Value::NativeFunction(_) | Value::Function(_) => Type::Function,
+            Value::Function(c) => c.node.get_type(),
+            // natives do not declare a signature
+            Value::NativeFunction(_) => Type::Function(None),
             Value::Unit => Type::Unit,
-            Value::List(_) => Type::List,
+            Value::List(l) => l.get_type(),
+            Value::Map(m) => m.get_type(),
+            Value::Namespace(_) => Type::Module,
+            Value::Record(r) => r.get_type(),
+            Value::Tuple(items) => Type::Tuple(items.iter().map(|x| x.get_type()).collect()),
+            Value::Variant(v) => v.get_type(),
+            Value::Constructor(c) => c.get_type(),
+            // only one side of a result is known from its value
+            Value::Option(None) => Type::Option(Box::new(Type::Infer)),
+            Value::Option(Some(v)) => Type::Option(Box::new(v.get_type())),
+            Value::Result(Ok(v)) => Type::Result(Box::new(v.get_type()), Box::new(Type::Infer)),
+            Value::Result(Err(e)) => Type::Result(Box::new(Type::Infer), Box::new(e.get_type())),
             Value::Uninitialized => unreachable!(),
         }
     }
@@ -108,62 +196,274 @@ impl Value {
         match self {
             Self::Int(i) => i.to_string(),
             Self::Float(i) => i.to_string(),
+            Self::Int64(i) => i.to_string(),
+            Self::Float64(i) => i.to_string(),
+            Self::BigInt(i) => i.display(),
             Self::Bool(b) => if *b { "true" } else { "false" }.into(),
+            Self::Char(c) => c.to_string(),
             Self::String(s) => s.clone(),
             Self::Function(_) => "[function]".to_string(),
             Self::NativeFunction(_) => "[native function]".to_string(),
             Self::Unit => "[unit]".to_string(),
             Self::List(l) => l.display(true),
+            Self::Map(m) => m.display(),
+            Self::Namespace(n) => n.display(),
+            Self::Record(r) => r.display(),
+            Self::Tuple(items) => {
+                let items: Vec<String> = items.iter().map(|x| x.display()).collect();
+                format!("({})", items.join(", "))
+            }
+            Self::Variant(v) => v.display(),
+            Self::Constructor(_) => "[constructor]".to_string(),
+            Self::Option(None) => "none".to_string(),
+            Self::Option(Some(v)) => format!("some({})", v.display()),
+            Self::Result(Ok(v)) => format!("ok({})", v.display()),
+            Self::Result(Err(e)) => format!("err({})", e.display()),
 
             Value::Uninitialized => unreachable!(),
         }
     }
 }
 
-/**
-* Operator implementations
+/*
+* Comparison
 * */
 
-impl Mul for Value {
-    type Output = Option<Value>;
-    fn mul(self, rhs: Value) -> Option<Value> {
-        match (self, rhs) {
-            (Value::Int(a), Value::Int(b)) => Some(Value::Int(a * b)),
-            (Value::Float(a), Value::Float(b)) => Some(Value::Float(a * b)),
-            _ => None,
+impl Value {
+    // structural equality, None if a function is involved
+    pub fn equals(&self, other: &Value) -> Option<bool> {
+        let res = match (self, other) {
+            (a, b) if a.is_numeric() && b.is_numeric() => {
+                compare_numbers(a, b) == Some(Ordering::Equal)
+            }
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Char(a), Value::Char(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Unit, Value::Unit) => true,
+            (Value::List(a), Value::List(b)) => {
+                a.length() == b.length() && all_equal(a.iter().zip(b.iter()))?
+            }
+            (Value::Tuple(a), Value::Tuple(b)) => {
+                a.len() == b.len() && all_equal(a.iter().zip(b.iter()))?
+            }
+            // entries are in key order, so equal maps have equal entries in the same order
+            (Value::Map(a), Value::Map(b)) => {
+                a.length() == b.length()
+                    && all_equal(
+                        a.iter()
+                            .zip(b.iter())
+                            .flat_map(|((k, v), (l, w))| [(k, l), (v, w)]),
+                    )?
+            }
+            (Value::Record(a), Value::Record(b)) => {
+                a.name == b.name
+                    && all_equal(a.fields.iter().zip(&b.fields).map(|(x, y)| (&x.1, &y.1)))?
+            }
+            (Value::Variant(a), Value::Variant(b)) => {
+                a.enum_name == b.enum_name
+                    && a.name == b.name
+                    && all_equal(a.fields.iter().zip(&b.fields))?
+            }
+            (Value::Option(Some(a)), Value::Option(Some(b))) => a.equals(b)?,
+            (Value::Option(None), Value::Option(None)) => true,
+            (Value::Result(Ok(a)), Value::Result(Ok(b)))
+            | (Value::Result(Err(a)), Value::Result(Err(b))) => a.equals(b)?,
+            (
+                Value::Function(_)
+                | Value::NativeFunction(_)
+                | Value::Constructor(_)
+                | Value::Namespace(_),
+                _,
+            )
+            | (
+                _,
+                Value::Function(_)
+                | Value::NativeFunction(_)
+                | Value::Constructor(_)
+                | Value::Namespace(_),
+            ) => {
+                return None;
+            }
+            _ => false,
+        };
+        Some(res)
+    }
+
+    // ordering of numbers, chars (by code point), and lexicographic ordering of strings, lists and tuples
+    // None if the values cannot be ordered
+    pub fn compare(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Char(a), Value::Char(b)) => Some(a.cmp(b)),
+            (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+            (Value::List(a), Value::List(b)) => compare_all(a.iter(), b.iter()),
+            (Value::Tuple(a), Value::Tuple(b)) => compare_all(a.iter(), b.iter()),
+            (a, b) => compare_numbers(a, b),
         }
     }
 }
 
-impl Div for Value {
-    type Output = Option<Value>;
-    fn div(self, rhs: Value) -> Option<Value> {
-        match (self, rhs) {
-            (Value::Int(a), Value::Int(b)) => Some(Value::Int(a / b)),
-            (Value::Float(a), Value::Float(b)) => Some(Value::Float(a / b)),
-            _ => None,
-        }
+// integers compare exactly (whatever their width), anything involving a float as f64
+fn compare_numbers(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+        (Value::Int64(a), Value::Int64(b)) => Some(a.cmp(b)),
+        (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+        (Value::Float64(a), Value::Float64(b)) => a.partial_cmp(b),
+        (a, b) => match (a.expect_integer(), b.expect_integer()) {
+            (Ok(a), Ok(b)) => Some(a.cmp(&b)),
+            _ => a
+                .expect_numeric()
+                .ok()?
+                .partial_cmp(&b.expect_numeric().ok()?),
+        },
     }
 }
 
-impl Add for Value {
-    type Output = Option<Value>;
-    fn add(self, rhs: Value) -> Option<Value> {
-        match (self, rhs) {
-            (Value::Int(a), Value::Int(b)) => Some(Value::Int(a + b)),
-            (Value::Float(a), Value::Float(b)) => Some(Value::Float(a + b)),
-            _ => None,
+fn all_equal<'a>(mut pairs: impl Iterator<Item = (&'a Value, &'a Value)>) -> Option<bool> {
+    pairs.try_fold(true, |acc, (a, b)| Some(acc && a.equals(b)?))
+}
+
+// compares element by element, a prefix is smaller than the whole
+fn compare_all<'a>(
+    a: impl Iterator<Item = &'a Value>,
+    mut b: impl Iterator<Item = &'a Value>,
+) -> Option<Ordering> {
+    for x in a {
+        let Some(y) = b.next() else {
+            return Some(Ordering::Greater);
+        };
+        match x.compare(y)? {
+            Ordering::Equal => continue,
+            ord => return Some(ord),
         }
     }
+    match b.next() {
+        Some(_) => Some(Ordering::Less),
+        None => Some(Ordering::Equal),
+    }
 }
 
-impl Sub for Value {
-    type Output = Option<Value>;
-    fn sub(self, rhs: Value) -> Option<Value> {
+/*
+* Operator implementations
+* */
+
+#[derive(Debug, PartialEq)]
+pub enum ArithmeticError {
+    // the operand types do not match, or are not numeric
+    InvalidOperands,
+    DivisionByZero,
+    Overflow,
+}
+
+// applies an integer operation in the given overflow mode
+macro_rules! int_op {
+    ($a:expr, $b:expr, $mode:expr, $checked:ident, $wrapping:ident, $saturating:ident) => {
+        match $mode {
+            Overflow::Checked => $a.$checked($b).ok_or(ArithmeticError::Overflow),
+            Overflow::Wrapping => Ok($a.$wrapping($b)),
+            Overflow::Saturating => Ok($a.$saturating($b)),
+        }
+    };
+}
+
+impl Value {
+    // arithmetic operators (+, -, *, /, %), operands must have the same type
+    // `+` also concatenates strings, floats follow IEEE 754 (x / 0.0 is infinite)
+    pub fn arithmetic(
+        self,
+        operator: &Operator,
+        rhs: Value,
+        mode: Overflow,
+    ) -> Result<Value, ArithmeticError> {
+        let is_division = matches!(operator, Operator::Div | Operator::Mod);
         match (self, rhs) {
-            (Value::Int(a), Value::Int(b)) => Some(Value::Int(a - b)),
-            (Value::Float(a), Value::Float(b)) => Some(Value::Float(a - b)),
-            _ => None,
+            (Value::Int(_), Value::Int(0)) | (Value::Int64(_), Value::Int64(0)) if is_division => {
+                Err(ArithmeticError::DivisionByZero)
+            }
+            (Value::BigInt(_), Value::BigInt(b)) if is_division && b.is_zero() => {
+                Err(ArithmeticError::DivisionByZero)
+            }
+            (Value::Int(a), Value::Int(b)) => {
+                let res = match operator {
+                    Operator::Add => int_op!(a, b, mode, checked_add, wrapping_add, saturating_add),
+                    Operator::Sub => int_op!(a, b, mode, checked_sub, wrapping_sub, saturating_sub),
+                    Operator::Mul => int_op!(a, b, mode, checked_mul, wrapping_mul, saturating_mul),
+                    Operator::Div => int_op!(a, b, mode, checked_div, wrapping_div, saturating_div),
+                    // the remainder of i32 min / -1 is 0 in every mode, only the division overflows
+                    Operator::Mod => Ok(a.wrapping_rem(b)),
+                    _ => Err(ArithmeticError::InvalidOperands),
+                };
+                res.map(Value::Int)
+            }
+            (Value::Int64(a), Value::Int64(b)) => {
+                let res = match operator {
+                    Operator::Add => int_op!(a, b, mode, checked_add, wrapping_add, saturating_add),
+                    Operator::Sub => int_op!(a, b, mode, checked_sub, wrapping_sub, saturating_sub),
+                    Operator::Mul => int_op!(a, b, mode, checked_mul, wrapping_mul, saturating_mul),
+                    Operator::Div => int_op!(a, b, mode, checked_div, wrapping_div, saturating_div),
+                    Operator::Mod => Ok(a.wrapping_rem(b)),
+                    _ => Err(ArithmeticError::InvalidOperands),
+                };
+                res.map(Value::Int64)
+            }
+            // arbitrary precision never overflows
+            (Value::BigInt(a), Value::BigInt(b)) => {
+                let res = match operator {
+                    Operator::Add => a + b,
+                    Operator::Sub => a - b,
+                    Operator::Mul => a * b,
+                    Operator::Div => a.checked_div(&b).ok_or(ArithmeticError::DivisionByZero)?,
+                    Operator::Mod => a.checked_rem(&b).ok_or(ArithmeticError::DivisionByZero)?,
+                    _ => return Err(ArithmeticError::InvalidOperands),
+                };
+                Ok(Value::BigInt(res))
+            }
+            (Value::Float(a), Value::Float(b)) => {
+                let res = match operator {
+                    Operator::Add => a + b,
+                    Operator::Sub => a - b,
+                    Operator::Mul => a * b,
+                    Operator::Div => a / b,
+                    _ => return Err(ArithmeticError::InvalidOperands),
+                };
+                Ok(Value::Float(res))
+            }
+            (Value::Float64(a), Value::Float64(b)) => {
+                let res = match operator {
+                    Operator::Add => a + b,
+                    Operator::Sub => a - b,
+                    Operator::Mul => a * b,
+                    Operator::Div => a / b,
+                    _ => return Err(ArithmeticError::InvalidOperands),
+                };
+                Ok(Value::Float64(res))
+            }
+            (Value::String(a), Value::String(b)) if *operator == Operator::Add => {
+                Ok(Value::String(a + &b))
+            }
+            _ => Err(ArithmeticError::InvalidOperands),
+        }
+    }
+
+    // prefix `-`
+    pub fn negate(self, mode: Overflow) -> Result<Value, ArithmeticError> {
+        match (self, mode) {
+            (Value::Int(x), Overflow::Checked) => x
+                .checked_neg()
+                .map(Value::Int)
+                .ok_or(ArithmeticError::Overflow),
+            (Value::Int(x), Overflow::Wrapping) => Ok(Value::Int(x.wrapping_neg())),
+            (Value::Int(x), Overflow::Saturating) => Ok(Value::Int(x.saturating_neg())),
+            (Value::Int64(x), Overflow::Checked) => x
+                .checked_neg()
+                .map(Value::Int64)
+                .ok_or(ArithmeticError::Overflow),
+            (Value::Int64(x), Overflow::Wrapping) => Ok(Value::Int64(x.wrapping_neg())),
+            (Value::Int64(x), Overflow::Saturating) => Ok(Value::Int64(x.saturating_neg())),
+            (Value::Float(x), _) => Ok(Value::Float(-x)),
+            (Value::Float64(x), _) => Ok(Value::Float64(-x)),
+            (Value::BigInt(x), _) => Ok(Value::BigInt(-x)),
+            _ => Err(ArithmeticError::InvalidOperands),
         }
     }
 }
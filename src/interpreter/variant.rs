@@ -0,0 +1,47 @@
+use crate::{
+    interpreter::value::Value,
+    parser::ast::{FunctionType, Type},
+};
+
+/*
+* Variant type
+* */
+
+#[derive(Debug, Clone)]
+pub struct Variant {
+    // name of the enum the variant belongs to
+    pub enum_name: String,
+    pub name: String,
+    pub fields: Vec<Value>,
+}
+
+impl Variant {
+    pub fn get_type(&self) -> Type {
+        Type::Named(self.enum_name.clone())
+    }
+
+    pub fn display(&self) -> String {
+        if self.fields.is_empty() {
+            return self.name.clone();
+        }
+        let fields: Vec<String> = self.fields.iter().map(|f| f.display()).collect();
+        format!("{}({})", self.name, fields.join(", "))
+    }
+}
+
+// a variant with payload, called like a function to build the variant
+#[derive(Debug, Clone)]
+pub struct Constructor {
+    pub enum_name: String,
+    pub name: String,
+    pub fields: Vec<Type>,
+}
+
+impl Constructor {
+    pub fn get_type(&self) -> Type {
+        Type::Function(Some(FunctionType {
+            params: self.fields.clone(),
+            returns: Box::new(Type::Named(self.enum_name.clone())),
+        }))
+    }
+}
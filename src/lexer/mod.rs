@@ -12,18 +12,7 @@ pub mod token;
 * */
 
 pub fn tokenize(input: String) -> Result<Vec<Token>, Error> {
-    let mut res = Vec::new();
-    let mut lexer = Lexer::new(input);
-    lexer.skip_whitespace();
-    while lexer.has_next() {
-        let tok = lexer.next_token()?;
-        if matches!(tok.kind, TokenKind::Comment(_)) {
-            continue;
-        };
-        res.push(tok);
-        lexer.skip_whitespace();
-    }
-    Ok(res)
+    Lexer::new(input).run()
 }
 
 struct Lexer {
@@ -43,6 +32,20 @@ impl Lexer {
         }
     }
 
+    fn run(mut self) -> Result<Vec<Token>, Error> {
+        let mut res = Vec::new();
+        self.skip_whitespace();
+        while self.has_next() {
+            let tok = self.next_token()?;
+            if matches!(tok.kind, TokenKind::Comment(_)) {
+                continue;
+            };
+            res.push(tok);
+            self.skip_whitespace();
+        }
+        Ok(res)
+    }
+
     fn next_token(&mut self) -> Result<Token, Error> {
         // need to report the line the token starts on
         // useful for error reporting
@@ -55,37 +58,10 @@ impl Lexer {
         // match every type of character
         let (kind, original) = match c {
             // Literal (Int or Float)
-            c if c.is_ascii_digit() => {
-                let digits = self.consume_while(|c| c.is_ascii_digit() || c == '.');
-                let int_err = Error::new(
-                    ErrorType::InvalidIntLiteral,
-                    position.clone(),
-                    &digits,
-                    None,
-                );
-                let float_err = Error::new(
-                    ErrorType::InvalidFloatLiteral,
-                    position.clone(),
-                    &digits,
-                    None,
-                );
-
-                if digits.contains('.') {
-                    let val: f32 = digits.parse().map_err(|_| float_err)?;
-                    (TokenKind::Literal(Literal::Float(val)), digits)
-                } else {
-                    let val: i32 = digits.parse().map_err(|_| int_err)?;
-                    (TokenKind::Literal(Literal::Int(val)), digits)
-                }
-            }
-            // Identifier, Keyword
-            c if c.is_alphanumeric() => {
-                let t = self.consume_while(|c| c.is_alphanumeric() || c == '_');
-                (classify_keyword_or_identifier(&t), t)
-            }
-            // Literal (String)
-            '"' => {
-                self.advance();
+            c if c.is_ascii_digit() => self.lex_number(&position)?,
+            // Literal (String), raw strings have no escapes or interpolation
+            'r' if self.is_next('"') => {
+                self.advance_n(2);
                 let s = self.consume_while(|c| c != '"');
                 if !self.has_next() {
                     return Err(Error::new(
@@ -98,6 +74,23 @@ impl Lexer {
                 self.advance();
                 (TokenKind::Literal(Literal::String(s.clone())), s)
             }
+            // Identifier, Keyword
+            c if c.is_alphanumeric() || c == '_' => {
+                let t = self.consume_while(|c| c.is_alphanumeric() || c == '_');
+                (classify_keyword_or_identifier(&t), t)
+            }
+            // Literal (Char)
+            '\'' => self.lex_char(&position)?,
+            // Literal (String), multi-line
+            '"' if self.is_next('"') && self.is_at(self.pos + 2, '"') => {
+                self.advance_n(3);
+                self.lex_string(&position, true)?
+            }
+            // Literal (String), possibly interpolated
+            '"' => {
+                self.advance();
+                self.lex_string(&position, false)?
+            }
             // Comment
             '/' if self.is_next('/') => {
                 self.advance_n(2);
@@ -121,16 +114,29 @@ impl Lexer {
             ',' => self.make_simple_token(TokenKind::Separator(Separator::Comma), ','),
             ';' => self.make_simple_token(TokenKind::Separator(Separator::Semicolon), ';'),
             ':' => self.make_simple_token(TokenKind::Separator(Separator::Colon), ':'),
+            '.' => self.make_simple_token(TokenKind::Separator(Separator::Dot), '.'),
             // Separator (Double)
             '-' if self.is_next('>') => {
                 self.advance_n(2);
                 (TokenKind::Separator(Separator::Arrow), "->".to_string())
             }
+            '=' if self.is_next('>') => {
+                self.advance_n(2);
+                (TokenKind::Separator(Separator::FatArrow), "=>".to_string())
+            }
             // Operators (Double)
             '!' if self.is_next('=') => {
                 self.advance_n(2);
                 (TokenKind::Operator(Operator::Ne), "!=".to_string())
             }
+            '<' if self.is_next('<') => {
+                self.advance_n(2);
+                (TokenKind::Operator(Operator::Shl), "<<".to_string())
+            }
+            '>' if self.is_next('>') => {
+                self.advance_n(2);
+                (TokenKind::Operator(Operator::Shr), ">>".to_string())
+            }
             '<' if self.is_next('=') => {
                 self.advance_n(2);
                 (TokenKind::Operator(Operator::Le), "<=".to_string())
@@ -143,6 +149,10 @@ impl Lexer {
                 self.advance_n(2);
                 (TokenKind::Operator(Operator::And), "&&".to_string())
             }
+            '|' if self.is_next('>') => {
+                self.advance_n(2);
+                (TokenKind::Operator(Operator::Pipe), "|>".to_string())
+            }
             '|' if self.is_next('|') => {
                 self.advance_n(2);
                 (TokenKind::Operator(Operator::Or), "||".to_string())
@@ -160,7 +170,11 @@ impl Lexer {
             '<' => self.make_simple_token(TokenKind::Operator(Operator::Lt), '<'),
             '>' => self.make_simple_token(TokenKind::Operator(Operator::Gt), '>'),
             '!' => self.make_simple_token(TokenKind::Operator(Operator::Not), '!'),
+            '&' => self.make_simple_token(TokenKind::Operator(Operator::BitAnd), '&'),
+            '|' => self.make_simple_token(TokenKind::Operator(Operator::BitOr), '|'),
+            '^' => self.make_simple_token(TokenKind::Operator(Operator::BitXor), '^'),
             '=' => self.make_simple_token(TokenKind::Operator(Operator::Assign), '='),
+            '?' => self.make_simple_token(TokenKind::Operator(Operator::Try), '?'),
             // Unknown
             _ => {
                 return Err(Error::new(
@@ -179,6 +193,404 @@ impl Lexer {
         Ok(token)
     }
 
+    // lexes decimal, hex (0x), binary (0b) and octal (0o) numbers
+    // with optional digit separators (1_000), exponents (1.5e-3) and type suffixes (10i32)
+    fn lex_number(&mut self, position: &Position) -> Result<(TokenKind, String), Error> {
+        // after a dot only the digits belong to the literal (tuple index, e.g. t.0.1)
+        if self.pos > 0 && self.src[self.pos - 1] == '.' {
+            let digits = self.consume_while(|c| c.is_ascii_digit());
+            let val: i32 = digits.parse().map_err(|_| {
+                Error::new(
+                    ErrorType::InvalidIntLiteral,
+                    position.clone(),
+                    &digits,
+                    Some("tuple index is too large"),
+                )
+            })?;
+            return Ok((TokenKind::Literal(Literal::Int(val)), digits));
+        }
+        let start = self.pos;
+        let radix = match (self.peek(), self.peek_next()) {
+            (Some('0'), Some('x')) => Some((16, "hex")),
+            (Some('0'), Some('b')) => Some((2, "binary")),
+            (Some('0'), Some('o')) => Some((8, "octal")),
+            _ => None,
+        };
+        if let Some((radix, name)) = radix {
+            self.advance_n(2);
+            let body = self.consume_while(|c| c.is_alphanumeric() || c == '_');
+            let original: String = self.src[start..self.pos].iter().collect();
+            let int_err = |msg: &str| {
+                Error::new(
+                    ErrorType::InvalidIntLiteral,
+                    position.clone(),
+                    &original,
+                    Some(msg),
+                )
+            };
+            // the suffix starts at the first character that cannot be a hex digit
+            let split = body
+                .find(|c: char| !c.is_ascii_hexdigit() && c != '_')
+                .unwrap_or(body.len());
+            let (digits, suffix) = body.split_at(split);
+            if digits.is_empty() {
+                return Err(int_err(&format!("expected {} digits", name)));
+            }
+            if let Some(c) = digits.chars().find(|c| *c != '_' && !c.is_digit(radix)) {
+                return Err(int_err(&format!(
+                    "invalid digit '{}' in a {} literal",
+                    c, name
+                )));
+            }
+            check_separators(digits).map_err(&int_err)?;
+            if !matches!(suffix, "" | "i32" | "i64") {
+                return Err(int_err(&format!("unknown suffix '{}'", suffix)));
+            }
+            let val = int_literal(digits, radix, suffix == "i64").map_err(int_err)?;
+            return Ok((TokenKind::Literal(val), original));
+        }
+        let mut is_float = false;
+        let integer = self.consume_while(|c| c.is_ascii_digit() || c == '_');
+        let mut fraction = None;
+        if self.peek() == Some('.') {
+            self.advance();
+            fraction = Some(self.consume_while(|c| c.is_ascii_digit() || c == '_'));
+            is_float = true;
+        }
+        let mut multiple_points = false;
+        while self.peek() == Some('.') && self.peek_next().is_some_and(|c| c.is_ascii_digit()) {
+            self.advance();
+            self.consume_while(|c| c.is_ascii_digit() || c == '_');
+            multiple_points = true;
+        }
+        let mut exponent = None;
+        if self.peek().is_some_and(|c| c == 'e' || c == 'E')
+            && !self
+                .peek_next()
+                .is_some_and(|c| c.is_alphabetic() || c == '_')
+        {
+            self.advance();
+            let sign = self.consume_while(|c| c == '+' || c == '-');
+            let digits = self.consume_while(|c| c.is_ascii_digit() || c == '_');
+            exponent = Some((sign, digits));
+            is_float = true;
+        }
+        let suffix = self.consume_while(|c| c.is_alphanumeric() || c == '_');
+        let original: String = self.src[start..self.pos].iter().collect();
+        let err = |ty: ErrorType, msg: &str| Error::new(ty, position.clone(), &original, Some(msg));
+        let float_err = |msg: &str| err(ErrorType::InvalidFloatLiteral, msg);
+        if multiple_points {
+            return Err(float_err("a number can only have one decimal point"));
+        }
+        match suffix.as_str() {
+            "" => (),
+            "f32" | "f64" => is_float = true,
+            "i32" | "i64" if is_float => {
+                return Err(float_err("a float literal cannot have an integer suffix"));
+            }
+            "i32" | "i64" => (),
+            _ => {
+                let ty = if is_float {
+                    ErrorType::InvalidFloatLiteral
+                } else {
+                    ErrorType::InvalidIntLiteral
+                };
+                return Err(err(ty, &format!("unknown suffix '{}'", suffix)));
+            }
+        }
+        if !is_float {
+            let int_err = |msg: &str| err(ErrorType::InvalidIntLiteral, msg);
+            check_separators(&integer).map_err(int_err)?;
+            let val = int_literal(&integer, 10, suffix == "i64").map_err(int_err)?;
+            return Ok((TokenKind::Literal(val), original));
+        }
+        check_separators(&integer).map_err(float_err)?;
+        let mut text = integer.replace('_', "");
+        if let Some(fraction) = fraction.filter(|f| !f.is_empty()) {
+            check_separators(&fraction).map_err(float_err)?;
+            text = format!("{}.{}", text, fraction.replace('_', ""));
+        }
+        if let Some((sign, digits)) = exponent {
+            if sign.len() > 1 {
+                return Err(float_err("an exponent can only have one sign"));
+            }
+            if digits.is_empty() {
+                return Err(float_err("expected digits in the exponent"));
+            }
+            check_separators(&digits).map_err(float_err)?;
+            text = format!("{}e{}{}", text, sign, digits.replace('_', ""));
+        }
+        if suffix == "f64" {
+            let val: f64 = text
+                .parse()
+                .ok()
+                .filter(|f: &f64| f.is_finite())
+                .ok_or_else(|| float_err("literal does not fit in f64"))?;
+            return Ok((TokenKind::Literal(Literal::Float64(val)), original));
+        }
+        let val: f32 = text
+            .parse()
+            .ok()
+            .filter(|f: &f32| f.is_finite())
+            .ok_or_else(|| float_err("literal does not fit in f32, use the f64 suffix"))?;
+        Ok((TokenKind::Literal(Literal::Float(val)), original))
+    }
+
+    // lexes the rest of a string after its opening quote(s)
+    // multi-line strings drop the line breaks after the opening and before the closing quotes
+    // and strip the indentation shared by their lines
+    fn lex_string(
+        &mut self,
+        position: &Position,
+        multiline: bool,
+    ) -> Result<(TokenKind, String), Error> {
+        let indent = if multiline {
+            self.multiline_indent()
+        } else {
+            0
+        };
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut original = String::new();
+        if multiline && self.peek() == Some('\n') {
+            self.advance();
+            self.skip_indent(indent);
+        }
+        loop {
+            match self.peek() {
+                None => {
+                    return Err(Error::new(
+                        ErrorType::UnterminatedStringLiteral,
+                        position.clone(),
+                        original,
+                        Some(if multiline {
+                            "you dropped this: \"\"\""
+                        } else {
+                            "you dropped this: \""
+                        }),
+                    ));
+                }
+                Some('"') if !multiline => break,
+                Some('"') if self.is_closing_triple_quote() => break,
+                Some('\n') if multiline && self.closes_after_whitespace() => {
+                    self.consume_while(|c| c != '"');
+                    break;
+                }
+                Some('\n') if multiline => {
+                    text.push('\n');
+                    original.push('\n');
+                    self.advance();
+                    self.skip_indent(indent);
+                }
+                Some('\\') => {
+                    let (c, escape) = self.lex_escape()?;
+                    text.push(c);
+                    original.push_str(&escape);
+                }
+                Some('$') if self.is_next('{') => {
+                    if !text.is_empty() {
+                        parts.push(StringPart::Text(std::mem::take(&mut text)));
+                    }
+                    let (tokens, code, start) = self.lex_embedded()?;
+                    original.push_str(&format!("${{{}}}", code));
+                    parts.push(StringPart::Code(tokens, start));
+                }
+                Some(c) => {
+                    text.push(c);
+                    original.push(c);
+                    self.advance();
+                }
+            }
+        }
+        self.advance_n(if multiline { 3 } else { 1 });
+        if parts.is_empty() {
+            return Ok((TokenKind::Literal(Literal::String(text)), original));
+        }
+        if !text.is_empty() {
+            parts.push(StringPart::Text(text));
+        }
+        Ok((TokenKind::Interpolation(parts), original))
+    }
+
+    // lexes a single unicode scalar value in single quotes, e.g. 'a', '\n' or '\u{1F600}'
+    fn lex_char(&mut self, position: &Position) -> Result<(TokenKind, String), Error> {
+        self.advance();
+        let (c, mut original) = match self.peek() {
+            Some('\\') => self.lex_escape()?,
+            Some(c) if c != '\'' && c != '\n' => {
+                self.advance();
+                (c, c.to_string())
+            }
+            _ => {
+                return Err(Error::new(
+                    ErrorType::InvalidCharLiteral,
+                    position.clone(),
+                    "'",
+                    Some("expected a character, e.g. 'a'"),
+                ));
+            }
+        };
+        if self.peek() != Some('\'') {
+            let rest = self.consume_while(|c| c != '\'' && c != '\n');
+            original.push_str(&rest);
+            if self.peek() == Some('\'') {
+                self.advance();
+                original.push('\'');
+            }
+            return Err(Error::new(
+                ErrorType::InvalidCharLiteral,
+                position.clone(),
+                format!("'{}", original),
+                Some("a char holds exactly one character, use double quotes for strings"),
+            ));
+        }
+        self.advance();
+        Ok((
+            TokenKind::Literal(Literal::Char(c)),
+            format!("'{}'", original),
+        ))
+    }
+
+    // lexes an escape sequence, returning the character and its source
+    fn lex_escape(&mut self) -> Result<(char, String), Error> {
+        let position = Position {
+            line: self.line,
+            col: self.col,
+        };
+        self.advance();
+        let c = self.advance().unwrap_or(' ');
+        let res = match c {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' | '"' | '\'' | '$' => c,
+            'u' if self.peek() == Some('{') => {
+                self.advance();
+                let hex = self.consume_while(|c| !matches!(c, '}' | '"' | '\'' | '\n'));
+                if self.peek() != Some('}') {
+                    return Err(Error::new(
+                        ErrorType::InvalidEscape,
+                        position,
+                        format!("\\u{{{}", hex),
+                        Some("expected '}' to close the unicode escape"),
+                    ));
+                }
+                self.advance();
+                let escape = format!("\\u{{{}}}", hex);
+                let c = u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| {
+                        Error::new(
+                            ErrorType::InvalidEscape,
+                            position,
+                            &escape,
+                            Some("expected a unicode scalar value in hex, e.g. \\u{1F600}"),
+                        )
+                    })?;
+                return Ok((c, escape));
+            }
+            _ => {
+                return Err(Error::new(
+                    ErrorType::InvalidEscape,
+                    position,
+                    format!("\\{}", c),
+                    Some("valid escapes are \\n, \\t, \\r, \\0, \\\\, \\\", \\', \\$ and \\u{...}"),
+                ));
+            }
+        };
+        Ok((res, format!("\\{}", c)))
+    }
+
+    // the smallest indentation of the lines of a multi-line string (after its opening line)
+    // the line of the closing quotes counts even though it is blank
+    fn multiline_indent(&self) -> usize {
+        let rest: String = self.src[self.pos..].iter().collect();
+        let Some(end) = rest.find("\"\"\"") else {
+            return 0;
+        };
+        let lines: Vec<&str> = rest[..end].split('\n').skip(1).collect();
+        let last = lines.len().saturating_sub(1);
+        lines
+            .iter()
+            .enumerate()
+            .filter(|(i, line)| *i == last || !line.trim().is_empty())
+            .map(|(_, line)| line.chars().take_while(|c| *c == ' ' || *c == '\t').count())
+            .min()
+            .unwrap_or(0)
+    }
+
+    fn skip_indent(&mut self, indent: usize) {
+        for _ in 0..indent {
+            if !self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+                break;
+            }
+            self.advance();
+        }
+    }
+
+    fn is_closing_triple_quote(&self) -> bool {
+        (0..3).all(|i| self.is_at(self.pos + i, '"'))
+    }
+
+    // true if only whitespace separates the next line break from the closing quotes
+    fn closes_after_whitespace(&self) -> bool {
+        let mut i = self.pos + 1;
+        while self.src.get(i).is_some_and(|c| *c == ' ' || *c == '\t') {
+            i += 1;
+        }
+        (0..3).all(|j| self.is_at(i + j, '"'))
+    }
+
+    // lexes the expression in "${...}", returning its tokens, source and the position of `${`
+    fn lex_embedded(&mut self) -> Result<(Vec<Token>, String, Position), Error> {
+        let start = Position {
+            line: self.line,
+            col: self.col,
+        };
+        self.advance_n(2);
+        let (line, col) = (self.line, self.col);
+        let mut code = String::new();
+        let mut depth = 0;
+        let mut in_string = false;
+        let mut in_char = false;
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(Error::new(
+                    ErrorType::UnterminatedStringLiteral,
+                    start,
+                    code,
+                    Some("expected '}' to close the interpolation"),
+                ));
+            };
+            match c {
+                // keep escaped characters of nested strings and chars, e.g. "${"\""}"
+                '\\' if in_string || in_char => {
+                    code.push(c);
+                    self.advance();
+                    if let Some(c) = self.advance() {
+                        code.push(c);
+                    }
+                    continue;
+                }
+                '"' if !in_char => in_string = !in_string,
+                '\'' if !in_string => in_char = !in_char,
+                '{' if !in_string && !in_char => depth += 1,
+                '}' if !in_string && !in_char && depth == 0 => break,
+                '}' if !in_string && !in_char => depth -= 1,
+                _ => (),
+            }
+            code.push(c);
+            self.advance();
+        }
+        self.advance();
+        let mut lexer = Lexer::new(code.clone());
+        lexer.line = line;
+        lexer.col = col;
+        Ok((lexer.run()?, code, start))
+    }
+
     fn has_next(&mut self) -> bool {
         self.peek().is_some()
     }
@@ -207,6 +619,10 @@ impl Lexer {
         self.src.get(self.pos + 1).cloned()
     }
 
+    fn is_at(&self, pos: usize, ch: char) -> bool {
+        self.src.get(pos).is_some_and(|c| *c == ch)
+    }
+
     fn is_next(&mut self, ch: char) -> bool {
         self.peek_next().is_some_and(|c| c == ch)
     }
@@ -243,21 +659,60 @@ impl Lexer {
     }
 }
 
+// an i32, or an i64 if the literal has the i64 suffix
+fn int_literal(digits: &str, radix: u32, wide: bool) -> Result<Literal, &'static str> {
+    let val = i64::from_str_radix(&digits.replace('_', ""), radix);
+    match val {
+        Ok(x) if wide => Ok(Literal::Int64(x)),
+        Ok(x) => i32::try_from(x)
+            .map(Literal::Int)
+            .map_err(|_| "literal does not fit in i32, use the i64 suffix"),
+        Err(_) if wide => Err("literal does not fit in i64"),
+        Err(_) => Err("literal does not fit in i64, use to_bigint(\"...\")"),
+    }
+}
+
+// digit separators must be surrounded by digits, e.g. 1_000 but not 1_ or 1__0
+fn check_separators(digits: &str) -> Result<(), &'static str> {
+    if digits.starts_with('_') || digits.ends_with('_') || digits.contains("__") {
+        return Err("digit separators must be between digits");
+    }
+    Ok(())
+}
+
 fn classify_keyword_or_identifier(identifier: &str) -> TokenKind {
     match identifier {
         "fn" => TokenKind::Keyword(Keyword::Fn),
         "function" => TokenKind::Keyword(Keyword::Function),
         "i32" => TokenKind::Keyword(Keyword::I32),
         "f32" => TokenKind::Keyword(Keyword::F32),
+        "i64" => TokenKind::Keyword(Keyword::I64),
+        "f64" => TokenKind::Keyword(Keyword::F64),
+        "bigint" => TokenKind::Keyword(Keyword::BigInt),
         "bool" => TokenKind::Keyword(Keyword::Bool),
+        "char" => TokenKind::Keyword(Keyword::Char),
         "string" => TokenKind::Keyword(Keyword::String),
         "true" => TokenKind::Keyword(Keyword::True),
         "false" => TokenKind::Keyword(Keyword::False),
         "if" => TokenKind::Keyword(Keyword::If),
         "else" => TokenKind::Keyword(Keyword::Else),
+        "match" => TokenKind::Keyword(Keyword::Match),
         "return" => TokenKind::Keyword(Keyword::Return),
+        "let" => TokenKind::Keyword(Keyword::Let),
+        "while" => TokenKind::Keyword(Keyword::While),
+        "for" => TokenKind::Keyword(Keyword::For),
+        "in" => TokenKind::Keyword(Keyword::In),
+        "break" => TokenKind::Keyword(Keyword::Break),
+        "continue" => TokenKind::Keyword(Keyword::Continue),
+        "mut" => TokenKind::Keyword(Keyword::Mut),
+        "struct" => TokenKind::Keyword(Keyword::Struct),
+        "enum" => TokenKind::Keyword(Keyword::Enum),
+        "import" => TokenKind::Keyword(Keyword::Import),
+        "export" => TokenKind::Keyword(Keyword::Export),
         "unit" => TokenKind::Keyword(Keyword::Unit),
         "list" => TokenKind::Keyword(Keyword::List),
+        "option" => TokenKind::Keyword(Keyword::Option),
+        "result" => TokenKind::Keyword(Keyword::Result),
         _ => TokenKind::Identifier(identifier.to_string()),
     }
 }
@@ -28,6 +28,15 @@ pub enum TokenKind {
     Comment(String),
     // cons is a _constructor_
     Cons,
+    // string with embedded expressions, e.g. "total: ${sum(xs)}"
+    Interpolation(Vec<StringPart>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StringPart {
+    Text(String),
+    // the tokens of an embedded expression, and where its `${` is
+    Code(Vec<Token>, Position),
 }
 
 /*
@@ -49,8 +58,17 @@ pub enum Operator {
     Ge,
     And,
     Or,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+    // `|>`
+    Pipe,
     Assign,
     Not,
+    // postfix `?`
+    Try,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -65,13 +83,18 @@ pub enum Separator {
     Semicolon,
     Colon,
     Arrow,
+    FatArrow,
+    Dot,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Literal {
     Int(i32),
     Float(f32),
+    Int64(i64),
+    Float64(f64),
     Bool(bool),
+    Char(char),
     String(String),
 }
 
@@ -81,7 +104,10 @@ impl Literal {
         match self {
             Self::Int(x) => LiteralValue::Int(x),
             Self::Float(x) => LiteralValue::Float(x),
+            Self::Int64(x) => LiteralValue::Int64(x),
+            Self::Float64(x) => LiteralValue::Float64(x),
             Self::Bool(x) => LiteralValue::Bool(x),
+            Self::Char(x) => LiteralValue::Char(x),
             Self::String(x) => LiteralValue::String(x),
         }
     }
@@ -92,18 +118,38 @@ pub enum Keyword {
     // control flow
     If,
     Else,
+    Match,
+    While,
+    For,
+    In,
+    Break,
+    Continue,
     // function-related
     Fn,
     Return,
+    // declarations
+    Let,
+    Mut,
+    Struct,
+    Enum,
+    // modules
+    Import,
+    Export,
     // literals
     True,
     False,
     // types
     I32,
     F32,
+    I64,
+    F64,
+    BigInt,
     Bool,
+    Char,
     String,
     Function,
     Unit,
     List,
+    Option,
+    Result,
 }
@@ -1,4 +1,6 @@
 pub mod interpreter;
-pub mod parser;
 pub mod lexer;
+pub mod loader;
+pub mod parser;
+pub mod typeck;
 pub mod utils;
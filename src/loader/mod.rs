@@ -0,0 +1,150 @@
+use crate::{
+    lexer::tokenize,
+    parser::{
+        ast::{Statement, StatementList},
+        parse,
+    },
+    utils::{
+        lang_error::{Error, ErrorType},
+        options::Options,
+        position::Position,
+    },
+};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+/*
+* Module loader
+* */
+
+// a parsed source file, with its imports resolved
+#[derive(Debug, Clone)]
+pub struct Module {
+    // where the file was found, for error messages
+    pub path: String,
+    // canonical path, identifies the module (see Import::module)
+    pub id: String,
+    pub ast: StatementList,
+}
+
+// reads the file and everything it imports
+// every file is loaded once and comes before the files importing it, the entry is last
+pub fn load(path: &str, options: &Options) -> Result<Vec<Module>, Error> {
+    let mut loader = Loader {
+        search_path: options.search_path.iter().map(PathBuf::from).collect(),
+        modules: Vec::new(),
+        loaded: HashSet::new(),
+        loading: Vec::new(),
+    };
+    let entry = PathBuf::from(path);
+    let id = canonical(&entry).ok_or_else(|| {
+        Error::new(
+            ErrorType::InvalidImport,
+            Position { line: 0, col: 0 },
+            path,
+            Some("no such file"),
+        )
+    })?;
+    loader.load_file(entry, id)?;
+    Ok(loader.modules)
+}
+
+struct Loader {
+    search_path: Vec<PathBuf>,
+    modules: Vec<Module>,
+    // ids of the modules already loaded
+    loaded: HashSet<String>,
+    // ids and paths of the files being loaded, each one imports the next
+    loading: Vec<(String, String)>,
+}
+
+impl Loader {
+    fn load_file(&mut self, path: PathBuf, id: String) -> Result<(), Error> {
+        let display = path.display().to_string();
+        // errors in imported files name the file
+        let is_entry = self.loading.is_empty();
+        let locate = |e: Error| if is_entry { e } else { e.in_file(&display) };
+        let source = fs::read_to_string(&path).map_err(|e| {
+            locate(Error::new(
+                ErrorType::InvalidImport,
+                Position { line: 0, col: 0 },
+                &display,
+                Some(&e.to_string()),
+            ))
+        })?;
+        let mut ast = tokenize(source).and_then(parse).map_err(locate)?;
+        self.loading.push((id.clone(), display.clone()));
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        for stmt in &mut ast.statements {
+            let Statement::Import(import) = stmt else {
+                continue;
+            };
+            let Some((found, dep)) = self.resolve(&dir, &import.path) else {
+                return Err(locate(Error::new(
+                    ErrorType::InvalidImport,
+                    import.position.clone(),
+                    &import.path,
+                    Some("no such file next to the importing file or in the search path"),
+                )));
+            };
+            if let Some(start) = self.loading.iter().position(|(l, _)| *l == dep) {
+                let mut chain: Vec<&str> = self.loading[start..]
+                    .iter()
+                    .map(|(_, p)| p.as_str())
+                    .collect();
+                chain.push(&self.loading[start].1);
+                return Err(locate(Error::new(
+                    ErrorType::ImportCycle,
+                    import.position.clone(),
+                    chain.join(" -> "),
+                    Some("files cannot import each other, move the shared code into another file"),
+                )));
+            }
+            if !self.loaded.contains(&dep) {
+                self.load_file(found, dep.clone())?;
+            }
+            import.module = Some(dep);
+        }
+        self.loading.pop();
+        self.loaded.insert(id.clone());
+        self.modules.push(Module {
+            path: display,
+            id,
+            ast,
+        });
+        Ok(())
+    }
+
+    // looks next to the importing file first, then in the search path
+    fn resolve(&self, dir: &Path, path: &str) -> Option<(PathBuf, String)> {
+        std::iter::once(dir)
+            .chain(self.search_path.iter().map(PathBuf::as_path))
+            .map(|d| normalize(&d.join(path)))
+            .find_map(|p| Some((p.clone(), canonical(&p)?)))
+    }
+}
+
+// removes `.` and `dir/..` without touching the file system, e.g. a/../b.lang is b.lang
+fn normalize(path: &Path) -> PathBuf {
+    let mut res = PathBuf::new();
+    for part in path.components() {
+        match part {
+            Component::CurDir => (),
+            Component::ParentDir
+                if matches!(res.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                res.pop();
+            }
+            part => res.push(part),
+        }
+    }
+    res
+}
+
+fn canonical(path: &Path) -> Option<String> {
+    let path = fs::canonicalize(path).ok().filter(|p| p.is_file())?;
+    Some(path.display().to_string())
+}
@@ -1,8 +1,15 @@
-use lang::{interpreter::interpret, lexer::tokenize, parser::parse};
+use lang::{
+    interpreter::interpret_modules,
+    lexer::tokenize,
+    loader::load,
+    parser::parse,
+    typeck::check_modules,
+    utils::options::{Options, Overflow},
+};
 use std::{env, fs::File, io::Read};
 const HELP: &str = include_str!("../docs/help");
 
-/**
+/*
 * Lang CLI
 * */
 
@@ -18,28 +25,44 @@ fn main() {
         "source" => handle_source(get_source_from_path(args.get(2))),
         "lexer" => handle_lexer(get_source_from_path(args.get(2))),
         "parser" => handle_parser(get_source_from_path(args.get(2))),
-        "run" => handle_run(get_source_from_path(args.get(2))),
+        "check" => match get_options(&args[2.min(args.len())..]) {
+            Ok(options) => handle_check(get_path(args.get(2)), options),
+            Err(e) => print!("\nerror: {}\n\n{}", e, HELP),
+        },
+        "run" => match get_options(&args[2.min(args.len())..]) {
+            Ok(options) => handle_run(get_path(args.get(2)), options),
+            Err(e) => print!("\nerror: {}\n\n{}", e, HELP),
+        },
         "repl" => handle_repl(),
         x => print!("\nerror: no such argument \"{}\"\n\n{}", x, HELP),
     }
 }
 
-/**
+/*
 * Handlers
 * */
 
-fn handle_run(source: String) {
-    let tokens = match tokenize(source) {
+fn handle_run(path: &str, options: Options) {
+    let mut modules = match load(path, &options) {
         Err(e) => return println!("{}", e.display()),
-        Ok(t) => t,
+        Ok(m) => m,
     };
-    let ast = match parse(tokens) {
+    if let Err(errors) = check_modules(&mut modules, &options) {
+        return errors.iter().for_each(|e| println!("{}", e.display()));
+    }
+    if let Err(e) = interpret_modules(modules, options) {
+        println!("{}", e.display())
+    }
+}
+
+fn handle_check(path: &str, options: Options) {
+    let mut modules = match load(path, &options) {
         Err(e) => return println!("{}", e.display()),
-        Ok(a) => a,
+        Ok(m) => m,
     };
-    match interpret(ast) {
-        Err(e) => return println!("{}", e.display()),
-        Ok(()) => (),
+    match check_modules(&mut modules, &options) {
+        Err(errors) => errors.iter().for_each(|e| println!("{}", e.display())),
+        Ok(()) => println!("no errors found"),
     }
 }
 
@@ -69,12 +92,35 @@ fn handle_repl() {
     todo!()
 }
 
-/**
+/*
 * Utility functions
 * */
 
+// flags after the path, e.g. `lang run main.lang --overflow=wrapping`
+fn get_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    for flag in args.iter().skip(1) {
+        match flag.split_once('=') {
+            None if flag == "--strict" => options.strict = true,
+            Some(("--path", dirs)) => {
+                options.search_path = dirs.split(':').map(String::from).collect();
+            }
+            Some(("--overflow", mode)) => {
+                options.overflow = Overflow::parse(mode)
+                    .ok_or_else(|| format!("invalid overflow mode \"{}\"", mode))?;
+            }
+            _ => return Err(format!("no such flag \"{}\"", flag)),
+        }
+    }
+    Ok(options)
+}
+
 fn get_source_from_path(path: Option<&String>) -> String {
-    get_file(path.expect("error: missing required argument <path>"))
+    get_file(get_path(path))
+}
+
+fn get_path(path: Option<&String>) -> &str {
+    path.expect("error: missing required argument <path>")
 }
 
 fn get_file(path: &str) -> String {
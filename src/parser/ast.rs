@@ -9,20 +9,72 @@ pub struct StatementList {
     pub statements: Vec<Statement>,
 }
 
+impl StatementList {
+    // names exported by a file: declarations and the variants of enums marked `export`
+    pub fn exports(&self) -> Vec<String> {
+        let mut res = Vec::new();
+        for stmt in &self.statements {
+            match stmt {
+                Statement::Assignment(a) if a.exported => res.push(a.identifier.clone()),
+                Statement::Enum(e) if e.exported => {
+                    res.extend(e.variants.iter().map(|v| v.name.clone()))
+                }
+                _ => (),
+            }
+        }
+        res
+    }
+
+    // names of the exported structs and enums, files importing them by name can use them
+    pub fn exported_types(&self) -> Vec<String> {
+        let mut res = Vec::new();
+        for stmt in &self.statements {
+            match stmt {
+                Statement::Struct(s) if s.exported => res.push(s.name.clone()),
+                Statement::Enum(e) if e.exported => res.push(e.name.clone()),
+                _ => (),
+            }
+        }
+        res
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Statement {
     Assignment(Assignment),
+    // destructuring declaration, e.g. (i32 q, i32 r) = divmod(7, 2)
+    Destructure(Destructure),
+    // assigns a new value to a mutable binding, e.g. x = x + 1
+    Reassignment(Reassignment),
     Expression(Expression),
     Return(Return),
+    Struct(StructDef),
+    Enum(EnumDef),
+    // while (cond) { ... }
+    While(WhileLoop),
+    // for x in list { ... }
+    For(ForLoop),
+    Break(Position),
+    Continue(Position),
+    // import "lib.lang" as lib, import { map, filter } from "lib.lang"
+    Import(Import),
 }
 
 impl Statement {
     pub fn get_position(&self) -> &Position {
-        return match self {
-            Statement::Return(x) => &x.expression.get_position(),
+        match self {
+            Statement::Return(x) => x.expression.get_position(),
             Statement::Assignment(x) => &x.position,
+            Statement::Destructure(x) => &x.position,
+            Statement::Reassignment(x) => &x.position,
             Statement::Expression(x) => x.get_position(),
-        };
+            Statement::Struct(x) => &x.position,
+            Statement::Enum(x) => &x.position,
+            Statement::While(x) => &x.position,
+            Statement::For(x) => &x.position,
+            Statement::Break(x) | Statement::Continue(x) => x,
+            Statement::Import(x) => &x.position,
+        }
     }
 
     pub fn expect_assignment(&self) -> Result<&Assignment, Error> {
@@ -60,6 +112,26 @@ pub enum Expression {
     ConsExp(ConsExp),
     // ListExp (list literal) e.g. [1, 2, 3]
     ListExp(ListExp),
+    // MapExp (map literal) e.g. { "a": 1, "b": 2 }, {:} is empty
+    MapExp(MapExp),
+    // RecordExp (record literal) e.g. Point { x: 1, y: 2 }
+    RecordExp(RecordExp),
+    // FieldExp (field access) e.g. p.x
+    FieldExp(FieldExp),
+    // TupleExp (tuple literal) e.g. (1, "one")
+    TupleExp(TupleExp),
+    // TupleIndexExp (positional access) e.g. t.0
+    TupleIndexExp(TupleIndexExp),
+    // UnaryExp e.g. !done, -x
+    UnaryExp(UnaryExp),
+    // BlockExp e.g. { i32 y = 2; y * y }
+    BlockExp(Block),
+    // TryExp (error propagation) e.g. try_head(l)?
+    TryExp(TryExp),
+    // MatchExp e.g. match (s) { Circle(r) => r, _ => 0.0 }
+    MatchExp(MatchExp),
+    // an argument left out of a partial application, e.g. `_` in sub(_, 1)
+    Placeholder(Position),
 }
 
 impl Expression {
@@ -74,7 +146,17 @@ impl Expression {
             Expression::IfExp(x) => &x.position,
             Expression::ConsExp(x) => &x.position,
             Expression::ListExp(x) => &x.position,
-            Expression::ParenExp(x) => &x.get_position(),
+            Expression::MapExp(x) => &x.position,
+            Expression::RecordExp(x) => &x.position,
+            Expression::FieldExp(x) => &x.position,
+            Expression::MatchExp(x) => &x.position,
+            Expression::TupleExp(x) => &x.position,
+            Expression::TryExp(x) => &x.position,
+            Expression::BlockExp(x) => &x.position,
+            Expression::UnaryExp(x) => &x.position,
+            Expression::TupleIndexExp(x) => &x.position,
+            Expression::ParenExp(x) => x.get_position(),
+            Expression::Placeholder(x) => x,
         }
     }
 }
@@ -90,7 +172,10 @@ pub enum LiteralValue {
     Unit,
     Int(i32),
     Float(f32),
+    Int64(i64),
+    Float64(f64),
     Bool(bool),
+    Char(char),
     String(String),
 }
 
@@ -106,6 +191,64 @@ pub struct Assignment {
     pub assignment_type: Type,
     pub identifier: String,
     pub expression: Expression,
+    // declared with `mut`, can be reassigned
+    pub mutable: bool,
+    // declared with `export`, visible to files importing this one
+    pub exported: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Import {
+    pub position: Position,
+    // as written, relative to the importing file or a directory of the search path
+    pub path: String,
+    pub names: ImportNames,
+    // the file the path resolved to, set by the loader
+    pub module: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ImportNames {
+    // import "lib.lang" as lib
+    Namespace(String),
+    // import { map, filter } from "lib.lang"
+    Selected(Vec<Identifier>),
+}
+
+#[derive(Debug, Clone)]
+pub struct WhileLoop {
+    pub position: Position,
+    pub cond: Expression,
+    pub body: StatementList,
+}
+
+#[derive(Debug, Clone)]
+pub struct ForLoop {
+    pub position: Position,
+    pub identifier: String,
+    pub iterable: Expression,
+    pub body: StatementList,
+}
+
+#[derive(Debug, Clone)]
+pub struct Reassignment {
+    pub position: Position,
+    pub identifier: String,
+    pub expression: Expression,
+}
+
+#[derive(Debug, Clone)]
+pub struct Destructure {
+    pub position: Position,
+    pub targets: Vec<Target>,
+    pub expression: Expression,
+}
+
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub position: Position,
+    pub target_type: Type,
+    pub identifier: String,
 }
 
 #[derive(Debug, Clone)]
@@ -121,11 +264,115 @@ pub struct Function {
     pub body: StatementList,
 }
 
+impl Function {
+    // fn(x) { second(first(x)) }, what `first >> second` evaluates to
+    // `first` and `second` are the names the composed functions are bound to
+    pub fn composition(position: Position, first: &str, second: &str) -> Function {
+        let identifier = |name: &str| {
+            Expression::IdentifierExp(Identifier {
+                position: position.clone(),
+                name: name.to_string(),
+            })
+        };
+        let call = |callee: Expression, arg: Expression| {
+            Expression::CallExp(Call {
+                position: position.clone(),
+                callee: Box::new(callee),
+                args: vec![Argument {
+                    position: position.clone(),
+                    name: None,
+                    value: arg,
+                }],
+            })
+        };
+        let inner = call(identifier(first), identifier("$x"));
+        Function {
+            position: position.clone(),
+            params: vec![Param {
+                position: position.clone(),
+                param_type: Type::Infer,
+                identifier: "$x".to_string(),
+                default: None,
+            }],
+            returns: Type::Infer,
+            body: StatementList {
+                statements: vec![Statement::Return(Return {
+                    expression: call(identifier(second), inner),
+                })],
+            },
+        }
+    }
+
+    // fn(params) { callee(args) }, what a partial application evaluates to
+    // `args` are names, of the supplied values or of the params, in the order of the call
+    pub fn forwarding(
+        position: Position,
+        callee: &str,
+        params: Vec<Param>,
+        args: Vec<String>,
+        returns: Type,
+    ) -> Function {
+        let identifier = |name: String| {
+            Expression::IdentifierExp(Identifier {
+                position: position.clone(),
+                name,
+            })
+        };
+        let call = Call {
+            position: position.clone(),
+            callee: Box::new(identifier(callee.to_string())),
+            args: args
+                .into_iter()
+                .map(|name| Argument {
+                    position: position.clone(),
+                    name: None,
+                    value: identifier(name),
+                })
+                .collect(),
+        };
+        Function {
+            position: position.clone(),
+            params,
+            returns,
+            body: StatementList {
+                statements: vec![Statement::Return(Return {
+                    expression: Expression::CallExp(call),
+                })],
+            },
+        }
+    }
+
+    // number of params without a default, they come first
+    pub fn required(&self) -> usize {
+        self.params
+            .iter()
+            .take_while(|p| p.default.is_none())
+            .count()
+    }
+
+    pub fn get_type(&self) -> Type {
+        self.get_type_with(self.params.len())
+    }
+
+    // the type of calls passing the first `n` params, the others take their defaults
+    pub fn get_type_with(&self, n: usize) -> Type {
+        Type::Function(Some(FunctionType {
+            params: self.params[..n]
+                .iter()
+                .map(|p| p.param_type.clone())
+                .collect(),
+            returns: Box::new(self.returns.clone()),
+        }))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Param {
     pub position: Position,
     pub param_type: Type,
     pub identifier: String,
+    // e.g. `1` in fn(x: i32, step: i32 = 1), evaluated in the closure's environment
+    pub default: Option<Expression>,
 }
 
 #[derive(Debug, Clone)]
@@ -137,23 +384,42 @@ pub struct Call {
 #[derive(Debug, Clone)]
 pub struct Argument {
     pub position: Position,
+    // e.g. `step` in range(0, 10, step: 2), named arguments come after the positional ones
+    pub name: Option<String>,
     pub value: Expression,
 }
 
 #[derive(Debug, Clone)]
 pub struct BinaryExp {
     pub position: Position,
+    pub operator_position: Position,
     pub left: Box<Expression>,
     pub right: Box<Expression>,
     pub operator: Operator,
 }
 
+#[derive(Debug, Clone)]
+pub struct UnaryExp {
+    pub position: Position,
+    // Not or Sub
+    pub operator: Operator,
+    pub operand: Box<Expression>,
+}
+
 #[derive(Debug, Clone)]
 pub struct IfExp {
     pub position: Position,
     pub if_cond: Box<Expression>,
-    pub then_branch: Box<Statement>,
-    pub else_branch: Option<Box<Statement>>,
+    pub then_branch: Block,
+    pub else_branch: Option<Block>,
+}
+
+// statements evaluating to the last one, e.g. { i32 y = 2; y * y }
+// a branch without braces is a block with a single statement
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub position: Position,
+    pub statements: StatementList,
 }
 
 #[derive(Debug, Clone)]
@@ -166,7 +432,7 @@ pub struct ConsExp {
 impl ConsExp {
     pub fn new(position: Position, head: Expression, tail: Expression) -> Self {
         Self {
-            position: position,
+            position,
             head: Box::new(head),
             tail: Box::new(tail),
         }
@@ -179,6 +445,175 @@ pub struct ListExp {
     pub items: Vec<Expression>,
 }
 
+#[derive(Debug, Clone)]
+pub struct MapExp {
+    pub position: Position,
+    // key, value
+    pub entries: Vec<(Expression, Expression)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TupleExp {
+    pub position: Position,
+    pub items: Vec<Expression>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TupleIndexExp {
+    pub position: Position,
+    pub tuple: Box<Expression>,
+    pub index: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct TryExp {
+    pub position: Position,
+    pub expression: Box<Expression>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StructDef {
+    pub position: Position,
+    pub name: String,
+    pub fields: Vec<Field>,
+    // declared with `export`, files importing it by name can use it
+    pub exported: bool,
+    // the module declaring it, set by the checker (see TypeKey)
+    pub module: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub position: Position,
+    pub identifier: String,
+    pub field_type: Type,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordExp {
+    pub position: Position,
+    pub name: String,
+    pub fields: Vec<FieldInit>,
+    // the module declaring the struct, set by the checker
+    pub module: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldInit {
+    pub position: Position,
+    pub identifier: String,
+    pub value: Expression,
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldExp {
+    pub position: Position,
+    pub record: Box<Expression>,
+    pub field: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct EnumDef {
+    pub position: Position,
+    pub name: String,
+    pub variants: Vec<VariantDef>,
+    // declared with `export`, its variants are visible to files importing this one
+    pub exported: bool,
+    // the module declaring it, set by the checker (see TypeKey)
+    pub module: Option<String>,
+}
+
+// identifies a struct or enum by the module declaring it and its name
+// files can declare types with the same name, only the ones a file declares or imports are nameable in it
+// the module is None in programs that were not loaded from files
+pub type TypeKey = (Option<String>, String);
+
+#[derive(Debug, Clone)]
+pub struct VariantDef {
+    pub position: Position,
+    pub name: String,
+    // payload types, empty for a variant without payload
+    pub fields: Vec<Type>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchExp {
+    pub position: Position,
+    pub subject: Box<Expression>,
+    pub arms: Vec<MatchArm>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub position: Position,
+    pub pattern: Pattern,
+    // the arm is only taken if the guard is true
+    pub guard: Option<Expression>,
+    pub body: Block,
+}
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    // `_` matches anything without binding
+    Wildcard(Position),
+    // binds the value, unless it names a variant without payload
+    Identifier(Identifier),
+    Literal(Literal),
+    // e.g. Rect(w, h)
+    Variant(VariantPattern),
+    // list of fixed length, e.g. [] or [a, b]
+    List(ListPattern),
+    // e.g. head :: tail
+    Cons(ConsPattern),
+    // e.g. (a, b)
+    Tuple(TuplePattern),
+}
+
+// some(x), ok(x) and err(x) patterns match options and results
+pub fn is_builtin_variant(name: &str) -> bool {
+    matches!(name, "some" | "ok" | "err")
+}
+
+impl Pattern {
+    pub fn get_position(&self) -> &Position {
+        match self {
+            Pattern::Wildcard(x) => x,
+            Pattern::Identifier(x) => &x.position,
+            Pattern::Literal(x) => &x.position,
+            Pattern::Variant(x) => &x.position,
+            Pattern::List(x) => &x.position,
+            Pattern::Cons(x) => &x.position,
+            Pattern::Tuple(x) => &x.position,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VariantPattern {
+    pub position: Position,
+    pub name: String,
+    pub fields: Vec<Pattern>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ListPattern {
+    pub position: Position,
+    pub items: Vec<Pattern>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConsPattern {
+    pub position: Position,
+    pub head: Box<Pattern>,
+    pub tail: Box<Pattern>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TuplePattern {
+    pub position: Position,
+    pub items: Vec<Pattern>,
+}
+
 /*
 * Keywords & Operators
 * */
@@ -187,16 +622,133 @@ pub struct ListExp {
 pub enum Type {
     I32,
     F32,
+    I64,
+    F64,
+    // arbitrary-precision integer
+    BigInt,
+    // a unicode scalar value
+    Char,
     String,
     Bool,
-    Function,
+    // None for a plain `function` (any signature)
+    Function(Option<FunctionType>),
     Unit,
-    List,
+    // None for a plain `list` (any element type)
+    List(Option<Box<Type>>),
+    // e.g. (i32, string)
+    Tuple(Vec<Type>),
+    // option<T>, a plain `option` has an Infer element
+    Option(Box<Type>),
+    // result<T, E>, a plain `result` has Infer params
+    Result(Box<Type>, Box<Type>),
+    // map<K, V>, a plain `map` has Infer params
+    Map(Box<Type>, Box<Type>),
+    // user-defined type (struct), referred to by name
+    Named(String),
+    // an imported file, e.g. `lib` in import "lib.lang" as lib
+    Module,
+    // not annotated (`let`, params), filled in by the type checker if possible
+    Infer,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionType {
+    pub params: Vec<Type>,
+    pub returns: Box<Type>,
+}
+
+impl Type {
+    // numeric promotion: the common type of two numeric types, None if there is none
+    //   i32 -> i64 -> bigint
+    //    |      |
+    //   f32 -> f64
+    // e.g. i32 and f32 meet at f32, i64 and f32 at f64, bigint and floats never meet
+    pub fn promote(a: &Type, b: &Type) -> Option<Type> {
+        let res = match (a, b) {
+            (a, b) if a == b && a.is_numeric() => a.clone(),
+            (Type::I32, Type::I64) | (Type::I64, Type::I32) => Type::I64,
+            (Type::I32 | Type::I64, Type::BigInt) | (Type::BigInt, Type::I32 | Type::I64) => {
+                Type::BigInt
+            }
+            (Type::I32, Type::F32) | (Type::F32, Type::I32) => Type::F32,
+            (Type::I32 | Type::I64 | Type::F32, Type::F64)
+            | (Type::F64, Type::I32 | Type::I64 | Type::F32)
+            | (Type::I64, Type::F32)
+            | (Type::F32, Type::I64) => Type::F64,
+            _ => return None,
+        };
+        Some(res)
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Type::I32 | Type::F32 | Type::I64 | Type::F64 | Type::BigInt
+        )
+    }
+
+    // returns true if a value of type `other` can be used where `self` is expected
+    // unparameterized types match any parameters (in both directions)
+    pub fn accepts(&self, other: &Type) -> bool {
+        match (self, other) {
+            (Type::Infer, _) | (_, Type::Infer) => true,
+            (Type::List(Some(a)), Type::List(Some(b))) => a.accepts(b),
+            (Type::List(_), Type::List(_)) => true,
+            (Type::Function(Some(a)), Type::Function(Some(b))) => {
+                a.params.len() == b.params.len()
+                    && a.params.iter().zip(&b.params).all(|(x, y)| x.accepts(y))
+                    && a.returns.accepts(&b.returns)
+            }
+            (Type::Function(_), Type::Function(_)) => true,
+            (Type::Option(a), Type::Option(b)) => a.accepts(b),
+            (Type::Result(a, x), Type::Result(b, y)) | (Type::Map(a, x), Type::Map(b, y)) => {
+                a.accepts(b) && x.accepts(y)
+            }
+            (Type::Tuple(a), Type::Tuple(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.accepts(y))
+            }
+            _ => self == other,
+        }
+    }
+
+    pub fn display(&self) -> String {
+        match self {
+            Type::I32 => "i32".to_string(),
+            Type::F32 => "f32".to_string(),
+            Type::I64 => "i64".to_string(),
+            Type::F64 => "f64".to_string(),
+            Type::BigInt => "bigint".to_string(),
+            Type::Char => "char".to_string(),
+            Type::String => "string".to_string(),
+            Type::Bool => "bool".to_string(),
+            Type::Unit => "unit".to_string(),
+            Type::Infer => "_".to_string(),
+            Type::Named(name) => name.clone(),
+            Type::Module => "module".to_string(),
+            Type::List(None) => "list".to_string(),
+            Type::List(Some(t)) => format!("list<{}>", t.display()),
+            Type::Tuple(items) => {
+                let items: Vec<String> = items.iter().map(|t| t.display()).collect();
+                format!("({})", items.join(", "))
+            }
+            Type::Option(t) if **t == Type::Infer => "option".to_string(),
+            Type::Option(t) => format!("option<{}>", t.display()),
+            Type::Result(t, e) if **t == Type::Infer && **e == Type::Infer => "result".to_string(),
+            Type::Result(t, e) => format!("result<{}, {}>", t.display(), e.display()),
+            Type::Map(k, v) if **k == Type::Infer && **v == Type::Infer => "map".to_string(),
+            Type::Map(k, v) => format!("map<{}, {}>", k.display(), v.display()),
+            Type::Function(None) => "function".to_string(),
+            Type::Function(Some(f)) => {
+                let params: Vec<String> = f.params.iter().map(|p| p.display()).collect();
+                format!("fn({}) -> {}", params.join(", "), f.returns.display())
+            }
+        }
+    }
 }
 
 pub type Operator = crate::lexer::token::Operator;
 
-/**
+/*
 * Printing AST
 * */
 
@@ -222,15 +774,82 @@ impl Statement {
             }
             Statement::Assignment(ast) => {
                 println!(
-                    "{}Assignment: {:?} {}",
-                    padding, ast.assignment_type, ast.identifier
+                    "{}Assignment: {}{}{} {}",
+                    padding,
+                    if ast.exported { "export " } else { "" },
+                    if ast.mutable { "mut " } else { "" },
+                    ast.assignment_type.display(),
+                    ast.identifier
                 );
                 ast.expression.print_ast(indent + 1);
             }
+            Statement::Reassignment(r) => {
+                println!("{}Reassignment: {}", padding, r.identifier);
+                r.expression.print_ast(indent + 1);
+            }
+            Statement::While(w) => {
+                println!("{}While", padding);
+                w.cond.print_ast(indent + 1);
+                w.body.print_ast(indent + 1);
+            }
+            Statement::For(f) => {
+                println!("{}For: {}", padding, f.identifier);
+                f.iterable.print_ast(indent + 1);
+                f.body.print_ast(indent + 1);
+            }
+            Statement::Break(_) => println!("{}Break", padding),
+            Statement::Import(imp) => match &imp.names {
+                ImportNames::Namespace(alias) => {
+                    println!("{}Import: \"{}\" as {}", padding, imp.path, alias)
+                }
+                ImportNames::Selected(names) => {
+                    let names: Vec<&str> = names.iter().map(|n| n.name.as_str()).collect();
+                    println!(
+                        "{}Import: {{ {} }} from \"{}\"",
+                        padding,
+                        names.join(", "),
+                        imp.path
+                    )
+                }
+            },
+            Statement::Continue(_) => println!("{}Continue", padding),
+            Statement::Destructure(dst) => {
+                let targets: Vec<String> = dst
+                    .targets
+                    .iter()
+                    .map(|t| format!("{} {}", t.target_type.display(), t.identifier))
+                    .collect();
+                println!("{}Destructure: ({})", padding, targets.join(", "));
+                dst.expression.print_ast(indent + 1);
+            }
             Statement::Expression(expr) => {
                 println!("{}Expression", padding);
                 expr.print_ast(indent + 1);
             }
+            Statement::Enum(def) => {
+                let export = if def.exported { "export " } else { "" };
+                println!("{}Enum: {}{}", padding, export, def.name);
+                for variant in &def.variants {
+                    let fields: Vec<String> = variant.fields.iter().map(|f| f.display()).collect();
+                    println!(
+                        "{}|  Variant: {}({})",
+                        padding,
+                        variant.name,
+                        fields.join(", ")
+                    );
+                }
+            }
+            Statement::Struct(def) => {
+                println!("{}Struct: {}", padding, def.name);
+                for field in &def.fields {
+                    println!(
+                        "{}|  Field: {} {}",
+                        padding,
+                        field.field_type.display(),
+                        field.identifier
+                    );
+                }
+            }
         }
     }
 }
@@ -254,6 +873,14 @@ impl Expression {
                 println!("{}List", padding);
                 lexp.items.iter().for_each(|x| x.print_ast(indent + 1));
             }
+            Expression::MapExp(mexp) => {
+                println!("{}Map", padding);
+                for (key, value) in &mexp.entries {
+                    println!("{}Entry", padding);
+                    key.print_ast(indent + 1);
+                    value.print_ast(indent + 1);
+                }
+            }
             Expression::FunctionExp(fexp) => {
                 println!("{}Function", padding);
                 fexp.body.print_ast(indent + 1);
@@ -263,16 +890,60 @@ impl Expression {
                 println!("{}Condition", padding);
                 iexp.if_cond.as_ref().print_ast(indent + 1);
                 println!("{}Then", padding);
-                iexp.then_branch.as_ref().print_ast(indent + 1);
+                iexp.then_branch.statements.print_ast(indent + 1);
                 if let Some(else_branch) = iexp.else_branch.as_ref() {
                     println!("{}Else", padding);
-                    else_branch.print_ast(indent + 1);
+                    else_branch.statements.print_ast(indent + 1);
                 }
             }
+            Expression::Placeholder(_) => println!("{}Placeholder", padding),
+            Expression::UnaryExp(uexp) => {
+                println!("{}UnaryExp: {:?}", padding, uexp.operator);
+                uexp.operand.print_ast(indent + 1);
+            }
+            Expression::BlockExp(block) => {
+                println!("{}Block", padding);
+                block.statements.print_ast(indent + 1);
+            }
             Expression::ParenExp(inner) => {
                 println!("{}ParenExp", padding);
                 inner.print_ast(indent + 1);
             }
+            Expression::RecordExp(rexp) => {
+                println!("{}Record: {}", padding, rexp.name);
+                for field in &rexp.fields {
+                    println!("{}Field: {}", padding, field.identifier);
+                    field.value.print_ast(indent + 1);
+                }
+            }
+            Expression::MatchExp(mexp) => {
+                println!("{}MatchExp", padding);
+                mexp.subject.print_ast(indent + 1);
+                for arm in &mexp.arms {
+                    println!("{}Arm: {:?}", padding, arm.pattern);
+                    if let Some(guard) = &arm.guard {
+                        println!("{}Guard", padding);
+                        guard.print_ast(indent + 1);
+                    }
+                    arm.body.statements.print_ast(indent + 1);
+                }
+            }
+            Expression::TupleExp(texp) => {
+                println!("{}Tuple", padding);
+                texp.items.iter().for_each(|x| x.print_ast(indent + 1));
+            }
+            Expression::TryExp(texp) => {
+                println!("{}TryExp", padding);
+                texp.expression.print_ast(indent + 1);
+            }
+            Expression::TupleIndexExp(texp) => {
+                println!("{}TupleIndex: {}", padding, texp.index);
+                texp.tuple.print_ast(indent + 1);
+            }
+            Expression::FieldExp(fexp) => {
+                println!("{}FieldExp: {}", padding, fexp.field);
+                fexp.record.print_ast(indent + 1);
+            }
             Expression::CallExp(cexp) => {
                 println!("{}CallExp", padding);
                 println!("{}Callee", padding);
@@ -17,7 +17,7 @@ pub fn parse(tokens: Vec<Token>) -> Result<StatementList, Error> {
         if !parser.has_next() {
             break;
         }
-        let statement = parser.parse_statement()?;
+        let statement = parser.parse_top_level()?;
         res.push(statement);
     }
     Ok(StatementList { statements: res })
@@ -26,16 +26,59 @@ pub fn parse(tokens: Vec<Token>) -> Result<StatementList, Error> {
 struct Parser {
     tokens: Vec<Token>,
     pos: usize,
+    // `name {` starts a block, not a record literal (see parse_head)
+    no_records: bool,
 }
 
 impl Parser {
     fn new(tokens: Vec<Token>) -> Self {
         Parser {
-            tokens: tokens,
+            tokens,
             pos: 0,
+            no_records: false,
         }
     }
 
+    // imports and exports are only allowed at the top level of a file
+    fn parse_top_level(&mut self) -> Result<Statement, Error> {
+        let tok = self
+            .peek()
+            .ok_or_else(|| Error::generic_eof("expected a statement"))?;
+        let res = match &tok.kind {
+            TokenKind::Keyword(Keyword::Import) => Statement::Import(self.parse_import()?),
+            // exported declaration, e.g. export let double = fn ...
+            TokenKind::Keyword(Keyword::Export) => {
+                self.advance();
+                match self.parse_statement()? {
+                    Statement::Assignment(mut a) => {
+                        a.position = tok.position;
+                        a.exported = true;
+                        Statement::Assignment(a)
+                    }
+                    Statement::Struct(mut s) => {
+                        s.exported = true;
+                        Statement::Struct(s)
+                    }
+                    Statement::Enum(mut e) => {
+                        e.exported = true;
+                        Statement::Enum(e)
+                    }
+                    other => {
+                        return Err(Error::new(
+                            ErrorType::UnexpectedTokenType,
+                            other.get_position().clone(),
+                            "export",
+                            Some("only declarations, structs and enums can be exported"),
+                        ));
+                    }
+                }
+            }
+            _ => return self.parse_statement(),
+        };
+        self.optional(TokenKind::Separator(Separator::Semicolon));
+        Ok(res)
+    }
+
     fn parse_statement(&mut self) -> Result<Statement, Error> {
         let tok = self
             .peek()
@@ -43,8 +86,54 @@ impl Parser {
         let res = match &tok.kind {
             // statement: return
             TokenKind::Keyword(Keyword::Return) => Ok(Statement::Return(self.parse_return()?)),
+            // statement: struct declaration
+            TokenKind::Keyword(Keyword::Struct) => Ok(Statement::Struct(self.parse_struct()?)),
+            // statement: enum declaration
+            TokenKind::Keyword(Keyword::Enum) => Ok(Statement::Enum(self.parse_enum()?)),
+            // statement: loops
+            TokenKind::Keyword(Keyword::While) => Ok(Statement::While(self.parse_while()?)),
+            TokenKind::Keyword(Keyword::For) => Ok(Statement::For(self.parse_for()?)),
+            TokenKind::Keyword(Keyword::Break) => {
+                self.advance();
+                Ok(Statement::Break(tok.position))
+            }
+            TokenKind::Keyword(Keyword::Continue) => {
+                self.advance();
+                Ok(Statement::Continue(tok.position))
+            }
+            // statement: destructuring declaration
+            TokenKind::Keyword(Keyword::Let)
+                if self
+                    .peek_n(1)
+                    .is_some_and(|tok| tok.kind == TokenKind::Separator(Separator::LParen)) =>
+            {
+                self.advance();
+                Ok(Statement::Destructure(
+                    self.parse_destructure(tok.position)?,
+                ))
+            }
+            _ if self.is_destructure() => Ok(Statement::Destructure(
+                self.parse_destructure(tok.position)?,
+            )),
             // match assignments (only other valid use of keywords)
-            kind if is_type(kind) => Ok(Statement::Assignment(self.parse_assignment()?)),
+            TokenKind::Keyword(Keyword::Let) => Ok(Statement::Assignment(self.parse_let()?)),
+            // mutable declaration with a type, e.g. mut i32 x = 0
+            TokenKind::Keyword(Keyword::Mut) => {
+                self.advance();
+                let mut a = self.parse_assignment()?;
+                a.position = tok.position;
+                a.mutable = true;
+                Ok(Statement::Assignment(a))
+            }
+            _ if self.is_assignment() => Ok(Statement::Assignment(self.parse_assignment()?)),
+            // statement: reassignment, e.g. x = x + 1
+            TokenKind::Identifier(_)
+                if self
+                    .peek_n(1)
+                    .is_some_and(|tok| tok.kind == TokenKind::Operator(Operator::Assign)) =>
+            {
+                Ok(Statement::Reassignment(self.parse_reassignment()?))
+            }
             // everything else is an expression
             // may or may not be valid though
             _ => Ok(Statement::Expression(self.parse_expression(0)?)),
@@ -53,6 +142,160 @@ impl Parser {
         res
     }
 
+    fn parse_struct(&mut self) -> Result<StructDef, Error> {
+        let struct_tok = self.expect(|x| matches!(x, TokenKind::Keyword(Keyword::Struct)))?;
+        let name = self.parse_identifier()?.name;
+        self.expect(|x| matches!(x, TokenKind::Separator(Separator::LBrace)))?;
+        let mut fields = Vec::new();
+        // fields: identifier ":" type, separated by commas
+        while !self.optional(TokenKind::Separator(Separator::RBrace)) {
+            let id = self.parse_identifier()?;
+            self.expect(|x| matches!(x, TokenKind::Separator(Separator::Colon)))?;
+            fields.push(Field {
+                position: id.position,
+                identifier: id.name,
+                field_type: self.parse_type()?,
+            });
+            if !self.optional(TokenKind::Separator(Separator::Comma)) {
+                self.expect(|x| matches!(x, TokenKind::Separator(Separator::RBrace)))?;
+                break;
+            }
+        }
+        Ok(StructDef {
+            position: struct_tok.position,
+            name,
+            fields,
+            exported: false,
+            module: None,
+        })
+    }
+
+    fn parse_enum(&mut self) -> Result<EnumDef, Error> {
+        let enum_tok = self.expect(|x| matches!(x, TokenKind::Keyword(Keyword::Enum)))?;
+        let name = self.parse_identifier()?.name;
+        self.expect(|x| matches!(x, TokenKind::Separator(Separator::LBrace)))?;
+        let mut variants = Vec::new();
+        // variants: identifier with optional payload types, separated by commas
+        while !self.optional(TokenKind::Separator(Separator::RBrace)) {
+            let id = self.parse_identifier()?;
+            let mut fields = Vec::new();
+            if self.optional(TokenKind::Separator(Separator::LParen)) {
+                loop {
+                    fields.push(self.parse_type()?);
+                    if self.optional(TokenKind::Separator(Separator::Comma)) {
+                        continue;
+                    }
+                    self.expect(|x| matches!(x, TokenKind::Separator(Separator::RParen)))?;
+                    break;
+                }
+            }
+            variants.push(VariantDef {
+                position: id.position,
+                name: id.name,
+                fields,
+            });
+            if !self.optional(TokenKind::Separator(Separator::Comma)) {
+                self.expect(|x| matches!(x, TokenKind::Separator(Separator::RBrace)))?;
+                break;
+            }
+        }
+        Ok(EnumDef {
+            position: enum_tok.position,
+            name,
+            variants,
+            exported: false,
+            module: None,
+        })
+    }
+
+    // import "path" as alias, or import { names } from "path"
+    fn parse_import(&mut self) -> Result<Import, Error> {
+        let import_tok = self.expect(|x| matches!(x, TokenKind::Keyword(Keyword::Import)))?;
+        let (path, names) = if self.optional(TokenKind::Separator(Separator::LBrace)) {
+            let mut names = Vec::new();
+            loop {
+                names.push(self.parse_identifier()?);
+                if self.optional(TokenKind::Separator(Separator::Comma)) {
+                    continue;
+                }
+                self.expect(|x| matches!(x, TokenKind::Separator(Separator::RBrace)))?;
+                break;
+            }
+            // `from` and `as` are not keywords
+            self.expect(|x| matches!(x, TokenKind::Identifier(w) if w == "from"))?;
+            (self.parse_import_path()?, ImportNames::Selected(names))
+        } else {
+            let path = self.parse_import_path()?;
+            self.expect(|x| matches!(x, TokenKind::Identifier(w) if w == "as"))?;
+            (path, ImportNames::Namespace(self.parse_identifier()?.name))
+        };
+        Ok(Import {
+            position: import_tok.position,
+            path,
+            names,
+            module: None,
+        })
+    }
+
+    fn parse_import_path(&mut self) -> Result<String, Error> {
+        let tok = self.expect(|x| {
+            matches!(
+                x,
+                TokenKind::Literal(crate::lexer::token::Literal::String(_))
+            )
+        })?;
+        match tok.kind {
+            TokenKind::Literal(crate::lexer::token::Literal::String(path)) => Ok(path),
+            _ => unreachable!(),
+        }
+    }
+
+    fn parse_while(&mut self) -> Result<WhileLoop, Error> {
+        let while_tok = self.expect(|x| matches!(x, TokenKind::Keyword(Keyword::While)))?;
+        self.expect(|x| matches!(x, TokenKind::Separator(Separator::LParen)))?;
+        let cond = self.parse_expression(0)?;
+        self.expect(|x| matches!(x, TokenKind::Separator(Separator::RParen)))?;
+        Ok(WhileLoop {
+            position: while_tok.position,
+            cond,
+            body: self.parse_loop_body()?,
+        })
+    }
+
+    fn parse_for(&mut self) -> Result<ForLoop, Error> {
+        let for_tok = self.expect(|x| matches!(x, TokenKind::Keyword(Keyword::For)))?;
+        let identifier = self.parse_identifier()?.name;
+        self.expect(|x| matches!(x, TokenKind::Keyword(Keyword::In)))?;
+        Ok(ForLoop {
+            position: for_tok.position,
+            identifier,
+            iterable: self.parse_head()?,
+            body: self.parse_loop_body()?,
+        })
+    }
+
+    // parses the expression before a body in braces, e.g. `xs` in for x in xs { ... }
+    // record literals need parens there, like in Rust: for x in (Bag { items: xs }).items { ... }
+    fn parse_head(&mut self) -> Result<Expression, Error> {
+        let old = std::mem::replace(&mut self.no_records, true);
+        let res = self.parse_expression(0);
+        self.no_records = old;
+        res
+    }
+
+    // parses a delimited expression, where record literals are allowed again
+    fn delimited<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, Error>) -> Result<T, Error> {
+        let old = std::mem::replace(&mut self.no_records, false);
+        let res = f(self);
+        self.no_records = old;
+        res
+    }
+
+    // loop bodies always have braces
+    fn parse_loop_body(&mut self) -> Result<StatementList, Error> {
+        Ok(self.parse_block()?.statements)
+    }
+
     fn parse_return(&mut self) -> Result<Return, Error> {
         self.advance();
         Ok(Return {
@@ -61,23 +304,109 @@ impl Parser {
     }
 
     fn parse_assignment(&mut self) -> Result<Assignment, Error> {
-        let type_tok = self.expect(|x| matches!(x, TokenKind::Keyword(_)))?;
-        let pos = type_tok.position.clone();
-        match type_tok.kind {
-            TokenKind::Keyword(Keyword::I32) => self.handle_assignment(Type::I32, pos),
-            TokenKind::Keyword(Keyword::F32) => self.handle_assignment(Type::F32, pos),
-            TokenKind::Keyword(Keyword::Bool) => self.handle_assignment(Type::Bool, pos),
-            TokenKind::Keyword(Keyword::String) => self.handle_assignment(Type::String, pos),
-            TokenKind::Keyword(Keyword::Function) => self.handle_assignment(Type::Function, pos),
-            TokenKind::Keyword(Keyword::Unit) => self.handle_assignment(Type::Unit, pos),
-            TokenKind::Keyword(Keyword::List) => self.handle_assignment(Type::List, pos),
-            _ => Err(Error::new(
-                ErrorType::UnexpectedTokenType,
-                pos,
-                type_tok.original,
-                Some("expected: type"),
-            )),
+        let pos = self
+            .peek()
+            .ok_or_else(|| Error::generic_eof("expected a type"))?
+            .position;
+        let a_type = self.parse_type()?;
+        self.handle_assignment(a_type, pos)
+    }
+
+    // declaration with an inferred type, e.g. let x = 1 or let mut x = 1
+    fn parse_let(&mut self) -> Result<Assignment, Error> {
+        let let_tok = self.expect(|x| matches!(x, TokenKind::Keyword(Keyword::Let)))?;
+        let mutable = self.optional(TokenKind::Keyword(Keyword::Mut));
+        let mut a = self.handle_assignment(Type::Infer, let_tok.position)?;
+        a.mutable = mutable;
+        Ok(a)
+    }
+
+    fn parse_reassignment(&mut self) -> Result<Reassignment, Error> {
+        let id = self.parse_identifier()?;
+        self.expect(|k| matches!(k, TokenKind::Operator(Operator::Assign)))?;
+        Ok(Reassignment {
+            position: id.position,
+            identifier: id.name,
+            expression: self.parse_expression(0)?,
+        })
+    }
+
+    // returns true if the next tokens are: type identifier "="
+    // function types start with "fn", so this requires lookahead
+    fn is_assignment(&mut self) -> bool {
+        if !self.peek().is_some_and(|tok| is_type(&tok.kind)) {
+            return false;
+        }
+        let start = self.pos;
+        let res = self.parse_type().is_ok()
+            && self
+                .peek()
+                .is_some_and(|tok| matches!(tok.kind, TokenKind::Identifier(_)))
+            && self
+                .peek_n(1)
+                .is_some_and(|tok| tok.kind == TokenKind::Operator(Operator::Assign));
+        self.pos = start;
+        res
+    }
+
+    // returns true if the next tokens are: "(" targets ")" "="
+    fn is_destructure(&mut self) -> bool {
+        if !self
+            .peek()
+            .is_some_and(|tok| tok.kind == TokenKind::Separator(Separator::LParen))
+        {
+            return false;
         }
+        let start = self.pos;
+        let res = self.parse_targets().is_ok()
+            && self
+                .peek()
+                .is_some_and(|tok| tok.kind == TokenKind::Operator(Operator::Assign));
+        self.pos = start;
+        res
+    }
+
+    fn parse_destructure(&mut self, pos: Position) -> Result<Destructure, Error> {
+        let targets = self.parse_targets()?;
+        self.expect(|k| matches!(k, TokenKind::Operator(Operator::Assign)))?;
+        Ok(Destructure {
+            position: pos,
+            targets,
+            expression: self.parse_expression(0)?,
+        })
+    }
+
+    // targets: "(" [type] identifier ("," [type] identifier)* ")"
+    fn parse_targets(&mut self) -> Result<Vec<Target>, Error> {
+        self.expect(|x| matches!(x, TokenKind::Separator(Separator::LParen)))?;
+        let mut targets = Vec::new();
+        loop {
+            let position = self
+                .peek()
+                .ok_or_else(|| Error::generic_eof("expected a target"))?
+                .position;
+            // a lone identifier has an inferred type
+            let untyped = matches!(
+                self.peek_n(1).map(|tok| tok.kind),
+                Some(TokenKind::Separator(Separator::Comma | Separator::RParen))
+            );
+            let target_type = if untyped {
+                Type::Infer
+            } else {
+                self.parse_type()?
+            };
+            targets.push(Target {
+                position,
+                target_type,
+                identifier: self.parse_identifier()?.name,
+            });
+            if self.optional(TokenKind::Separator(Separator::Comma)) {
+                continue;
+            }
+            self.expect(|x| matches!(x, TokenKind::Separator(Separator::RParen)))?;
+            break;
+        }
+        Ok(targets)
     }
 
     fn handle_assignment(&mut self, a_type: Type, pos: Position) -> Result<Assignment, Error> {
@@ -92,6 +421,8 @@ impl Parser {
             assignment_type: a_type,
             identifier: ident_str,
             expression: self.parse_expression(0)?,
+            mutable: false,
+            exported: false,
         })
     }
 
@@ -105,14 +436,40 @@ impl Parser {
             TokenKind::Literal(_) | TokenKind::Keyword(Keyword::True | Keyword::False) => {
                 Expression::LiteralExp(self.parse_literal_token()?)
             }
+            TokenKind::Interpolation(_) => self.parse_interpolation()?,
+            TokenKind::Identifier(_) if !self.no_records && self.is_record_literal() => {
+                Expression::RecordExp(self.parse_record_expr()?)
+            }
             TokenKind::Identifier(_) => Expression::IdentifierExp(self.parse_identifier()?),
-            TokenKind::Separator(Separator::LParen) => self.parse_paren_expr()?,
-            TokenKind::Keyword(Keyword::Fn) => Expression::FunctionExp(self.parse_function()?),
+            TokenKind::Separator(Separator::LParen) => self.delimited(Self::parse_paren_expr)?,
+            TokenKind::Keyword(Keyword::Fn) => {
+                Expression::FunctionExp(self.delimited(Self::parse_function)?)
+            }
             TokenKind::Keyword(Keyword::If) => Expression::IfExp(self.parse_if_expr()?),
+            TokenKind::Keyword(Keyword::Match) => Expression::MatchExp(self.parse_match_expr()?),
+            TokenKind::Separator(Separator::LBrace) if self.is_map_literal() => {
+                Expression::MapExp(self.delimited(Self::parse_map_expr)?)
+            }
+            TokenKind::Separator(Separator::LBrace) => {
+                Expression::BlockExp(self.delimited(Self::parse_block)?)
+            }
+            // prefix operators bind tighter than any binary operator
+            TokenKind::Operator(Operator::Not | Operator::Sub) => {
+                let op = self.advance().unwrap();
+                let operand = self.parse_expression(UNARY_PRECEDENCE)?;
+                Expression::UnaryExp(UnaryExp {
+                    position: pos.clone(),
+                    operator: match op.kind {
+                        TokenKind::Operator(op) => op,
+                        _ => unreachable!(),
+                    },
+                    operand: Box::new(operand),
+                })
+            }
             TokenKind::Separator(Separator::LBracket) => {
                 // list expression is a list literal, e.g. [1, 2, 3]
                 // not to be confused with cons, which is parsed below
-                Expression::ListExp(self.parse_list_expr()?)
+                Expression::ListExp(self.delimited(Self::parse_list_expr)?)
             }
             _ => {
                 return Err(Error::new(
@@ -126,8 +483,47 @@ impl Parser {
         // right-recursive descent (if operator is present)
         while let Some(tok) = self.peek() {
             // function calls
-            if matches!(tok.kind, TokenKind::Separator(Separator::LParen)) {
-                lhs = Expression::CallExp(self.parse_call(lhs)?);
+            // the paren must be on the same line, otherwise it starts a new statement
+            // e.g. a tuple or destructuring declaration
+            if matches!(tok.kind, TokenKind::Separator(Separator::LParen)) && !self.on_new_line() {
+                lhs = Expression::CallExp(self.delimited(|p| p.parse_call(lhs))?);
+                continue;
+            }
+            // error propagation, e.g. try_head(l)?
+            if let Some(tok) = self
+                .peek()
+                .filter(|tok| tok.kind == TokenKind::Operator(Operator::Try))
+            {
+                self.advance();
+                lhs = Expression::TryExp(TryExp {
+                    position: tok.position,
+                    expression: Box::new(lhs),
+                });
+                continue;
+            }
+            // field access
+            if self.optional(TokenKind::Separator(Separator::Dot)) {
+                // positional access, e.g. t.0
+                if let Some(Token {
+                    kind: TokenKind::Literal(crate::lexer::token::Literal::Int(index)),
+                    position,
+                    ..
+                }) = self.peek()
+                {
+                    self.advance();
+                    lhs = Expression::TupleIndexExp(TupleIndexExp {
+                        position,
+                        tuple: Box::new(lhs),
+                        index: index as usize,
+                    });
+                    continue;
+                }
+                let field = self.parse_identifier()?;
+                lhs = Expression::FieldExp(FieldExp {
+                    position: field.position,
+                    record: Box::new(lhs),
+                    field: field.name,
+                });
                 continue;
             }
             // check for operator
@@ -148,13 +544,18 @@ impl Parser {
             };
             // handle binary operator
             let prec = get_precedence(&op);
-            if prec < min_prec {
+            if prec == 0 || prec < min_prec {
                 break;
             }
-            self.advance();
+            let operator_position = self.advance().unwrap().position;
             let rhs = self.parse_expression(prec + 1)?;
+            if op == Operator::Pipe {
+                lhs = Expression::CallExp(pipe(lhs, rhs));
+                continue;
+            }
             lhs = Expression::BinaryExp(BinaryExp {
                 position: pos.clone(),
+                operator_position,
                 left: Box::new(lhs),
                 right: Box::new(rhs),
                 operator: op,
@@ -177,9 +578,70 @@ impl Parser {
         }
         // args
         loop {
+            // a named argument is an identifier followed by a colon, e.g. step: 2
+            let name = match (self.peek(), self.peek_n(1)) {
+                (Some(tok), Some(next))
+                    if matches!(next.kind, TokenKind::Separator(Separator::Colon)) =>
+                {
+                    match tok.kind {
+                        TokenKind::Identifier(name) => Some((name, tok.position)),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            if let Some((name, position)) = &name {
+                self.advance();
+                self.advance();
+                if args
+                    .iter()
+                    .any(|a: &Argument| a.name.as_ref() == Some(name))
+                {
+                    let message = format!("argument `{}` given more than once", name);
+                    return Err(Error::new(
+                        ErrorType::InvalidParams,
+                        position.clone(),
+                        name,
+                        Some(&message),
+                    ));
+                }
+            } else if let Some(named) = args.iter().rev().find_map(|a| a.name.as_ref()) {
+                let tok = self
+                    .peek()
+                    .ok_or_else(|| Error::generic_eof("incomplete argument list"))?;
+                let message = format!(
+                    "positional arguments must come before named ones, found one after `{}`",
+                    named
+                );
+                return Err(Error::new(
+                    ErrorType::InvalidParams,
+                    tok.position,
+                    tok.original,
+                    Some(&message),
+                ));
+            }
+            // a placeholder is a lone `_`, e.g. sub(_, 1)
+            let placeholder = match (self.peek(), self.peek_n(1)) {
+                (Some(tok), Some(next)) if tok.kind == TokenKind::Identifier("_".to_string()) => {
+                    matches!(
+                        next.kind,
+                        TokenKind::Separator(Separator::Comma | Separator::RParen)
+                    )
+                    .then_some(tok.position)
+                }
+                _ => None,
+            };
+            let value = match placeholder {
+                Some(position) => {
+                    self.advance();
+                    Expression::Placeholder(position)
+                }
+                None => self.parse_expression(0)?,
+            };
             args.push(Argument {
                 position: pos.clone(),
-                value: self.parse_expression(0)?,
+                name: name.map(|(name, _)| name),
+                value,
             });
             if self.optional(TokenKind::Separator(Separator::Comma)) {
                 continue;
@@ -187,6 +649,24 @@ impl Parser {
             break;
         }
         self.expect(|x| matches!(x, TokenKind::Separator(Separator::RParen)))?;
+        // named arguments are matched to params, so the call cannot be partial
+        let named = args.iter().find_map(|a| a.name.as_ref());
+        if let Some(p) = args
+            .iter()
+            .find(|a| matches!(a.value, Expression::Placeholder(_)))
+            && let Some(named) = named
+        {
+            let message = format!(
+                "placeholders cannot be combined with named arguments, like `{}`",
+                named
+            );
+            return Err(Error::new(
+                ErrorType::InvalidParams,
+                p.value.get_position().clone(),
+                "_",
+                Some(&message),
+            ));
+        }
 
         Ok(Call {
             position: pos,
@@ -211,10 +691,156 @@ impl Parser {
         }
         Ok(ListExp {
             position: tok.position,
-            items: items,
+            items,
+        })
+    }
+
+    // returns true if the next tokens are: "{" ":" "}" or "{" expression ":"
+    // (a block never starts with an expression followed by a colon)
+    fn is_map_literal(&mut self) -> bool {
+        let kind = |n: usize| self.peek_n(n).map(|tok| tok.kind);
+        if kind(1) == Some(TokenKind::Separator(Separator::Colon)) {
+            return kind(2) == Some(TokenKind::Separator(Separator::RBrace));
+        }
+        let start = self.pos;
+        self.advance();
+        let res = self.parse_expression(0).is_ok()
+            && self
+                .peek()
+                .is_some_and(|tok| tok.kind == TokenKind::Separator(Separator::Colon));
+        self.pos = start;
+        res
+    }
+
+    // map: "{" ":" "}" | "{" expression ":" expression ("," expression ":" expression)* [","] "}"
+    fn parse_map_expr(&mut self) -> Result<MapExp, Error> {
+        let open = self.expect(|x| matches!(x, TokenKind::Separator(Separator::LBrace)))?;
+        let mut entries = Vec::new();
+        if self.optional(TokenKind::Separator(Separator::Colon)) {
+            self.expect(|x| matches!(x, TokenKind::Separator(Separator::RBrace)))?;
+            return Ok(MapExp {
+                position: open.position,
+                entries,
+            });
+        }
+        loop {
+            let key = self.parse_expression(0)?;
+            self.expect(|x| matches!(x, TokenKind::Separator(Separator::Colon)))?;
+            entries.push((key, self.parse_expression(0)?));
+            if !self.optional(TokenKind::Separator(Separator::Comma)) {
+                self.expect(|x| matches!(x, TokenKind::Separator(Separator::RBrace)))?;
+                break;
+            }
+            if self.optional(TokenKind::Separator(Separator::RBrace)) {
+                break;
+            }
+        }
+        Ok(MapExp {
+            position: open.position,
+            entries,
+        })
+    }
+
+    // returns true if the next tokens are: identifier "{" (identifier ":" | "}")
+    fn is_record_literal(&self) -> bool {
+        let kind = |n: usize| self.peek_n(n).map(|tok| tok.kind);
+        if kind(1) != Some(TokenKind::Separator(Separator::LBrace)) {
+            return false;
+        }
+        match kind(2) {
+            Some(TokenKind::Separator(Separator::RBrace)) => true,
+            Some(TokenKind::Identifier(_)) => {
+                kind(3) == Some(TokenKind::Separator(Separator::Colon))
+            }
+            _ => false,
+        }
+    }
+
+    fn parse_record_expr(&mut self) -> Result<RecordExp, Error> {
+        let name = self.parse_identifier()?;
+        self.expect(|x| matches!(x, TokenKind::Separator(Separator::LBrace)))?;
+        let mut fields = Vec::new();
+        while !self.optional(TokenKind::Separator(Separator::RBrace)) {
+            let id = self.parse_identifier()?;
+            self.expect(|x| matches!(x, TokenKind::Separator(Separator::Colon)))?;
+            fields.push(FieldInit {
+                position: id.position,
+                identifier: id.name,
+                value: self.parse_expression(0)?,
+            });
+            if !self.optional(TokenKind::Separator(Separator::Comma)) {
+                self.expect(|x| matches!(x, TokenKind::Separator(Separator::RBrace)))?;
+                break;
+            }
+        }
+        Ok(RecordExp {
+            position: name.position,
+            name: name.name,
+            fields,
+            module: None,
         })
     }
 
+    // lowers "a${x}b" into "a" + to_string(x) + "b"
+    fn parse_interpolation(&mut self) -> Result<Expression, Error> {
+        let tok = self.advance().unwrap();
+        let TokenKind::Interpolation(parts) = tok.kind else {
+            return Err(Error::generic_utt(tok));
+        };
+        let pos = tok.position;
+        let mut res: Option<Expression> = None;
+        for part in parts {
+            let expr = match part {
+                StringPart::Text(s) => Expression::LiteralExp(Literal {
+                    position: pos.clone(),
+                    value: LiteralValue::String(s),
+                }),
+                StringPart::Code(tokens, start) => {
+                    let mut inner = Parser::new(tokens);
+                    // running out of tokens means the `${...}` is empty or incomplete
+                    let value = inner.parse_expression(0).map_err(|e| match e.error_type {
+                        ErrorType::UnexpectedEOF => Error {
+                            position: start,
+                            ..e
+                        },
+                        _ => e,
+                    })?;
+                    if let Some(tok) = inner.peek() {
+                        return Err(Error::new(
+                            ErrorType::UnexpectedTokenType,
+                            tok.position,
+                            tok.original,
+                            Some("expected '}' to close the interpolation"),
+                        ));
+                    }
+                    Expression::CallExp(Call {
+                        position: value.get_position().clone(),
+                        callee: Box::new(Expression::IdentifierExp(Identifier {
+                            position: pos.clone(),
+                            name: "to_string".to_string(),
+                        })),
+                        args: vec![Argument {
+                            position: value.get_position().clone(),
+                            name: None,
+                            value,
+                        }],
+                    })
+                }
+            };
+            res = Some(match res {
+                None => expr,
+                Some(lhs) => Expression::BinaryExp(BinaryExp {
+                    position: pos.clone(),
+                    operator_position: pos.clone(),
+                    left: Box::new(lhs),
+                    right: Box::new(expr),
+                    operator: Operator::Add,
+                }),
+            });
+        }
+        res.ok_or_else(|| Error::generic_eof("expected an interpolated string"))
+    }
+
     fn parse_literal_token(&mut self) -> Result<Literal, Error> {
         let tok = self.advance().unwrap();
         let pos = tok.position.clone();
@@ -255,10 +881,12 @@ impl Parser {
         let pos = fn_keyword.position.clone();
         // parse param list
         let params = self.parse_params()?;
-        // parse return type
-        self.expect(|x| matches!(x, TokenKind::Separator(Separator::Arrow)))?;
-        let tok = self.expect(is_type)?;
-        let returns = get_type_from_keyword(tok)?;
+        // parse return type, inferred when left out
+        let returns = if self.optional(TokenKind::Separator(Separator::Arrow)) {
+            self.parse_type()?
+        } else {
+            Type::Infer
+        };
         self.expect(|x| matches!(x, TokenKind::Separator(Separator::LBrace)))?;
         let mut statement_list: Vec<Statement> = Vec::new();
         loop {
@@ -274,12 +902,25 @@ impl Parser {
         }
         // last statement must be something that can be returned
         let last = statement_list.last();
-        if last.is_none() || matches!(last.unwrap(), Statement::Assignment(_)) {
+        if last.is_none()
+            || matches!(
+                last.unwrap(),
+                Statement::Assignment(_)
+                    | Statement::Destructure(_)
+                    | Statement::Reassignment(_)
+                    | Statement::While(_)
+                    | Statement::For(_)
+                    | Statement::Break(_)
+                    | Statement::Continue(_)
+                    | Statement::Struct(_)
+                    | Statement::Enum(_)
+            )
+        {
             return Err(Error::new(
                 ErrorType::FunctionShouldEndWithReturn,
                 pos,
                 // need to get variant name here
-                &last.map_or("None".into(), |last| format!("{:?}", last)),
+                last.map_or("None".into(), |last| format!("{:?}", last)),
                 Some("function must return"),
             ));
         }
@@ -295,8 +936,8 @@ impl Parser {
         }
         Ok(Function {
             position: pos,
-            params: params,
-            returns: returns,
+            params,
+            returns,
             body: StatementList {
                 statements: statement_list,
             },
@@ -313,7 +954,17 @@ impl Parser {
         }
         // loop: params until Rparen
         loop {
-            res.push(self.parse_param()?);
+            let param = self.parse_param()?;
+            // required params are the leading ones, calls with fewer arguments are partial
+            if param.default.is_none() && res.iter().any(|p: &Param| p.default.is_some()) {
+                return Err(Error::new(
+                    ErrorType::UnexpectedTokenType,
+                    param.position,
+                    param.identifier,
+                    Some("params without a default must come before the ones with a default"),
+                ));
+            }
+            res.push(param);
             // case: finished
             if self.optional(TokenKind::Separator(Separator::RParen)) {
                 break;
@@ -338,19 +989,12 @@ impl Parser {
     }
 
     fn parse_param(&mut self) -> Result<Param, Error> {
-        // match identifier -> colon -> type
+        // match identifier -> (optional) colon -> type -> (optional) `=` default
         let id = self.expect(|x| matches!(x, TokenKind::Identifier(_)))?;
-        self.expect(|x| matches!(x, TokenKind::Separator(Separator::Colon)))?;
-        let type_token = self.expect(is_type)?;
-        let ty = match type_token.kind {
-            TokenKind::Keyword(Keyword::Bool) => Type::Bool,
-            TokenKind::Keyword(Keyword::I32) => Type::I32,
-            TokenKind::Keyword(Keyword::F32) => Type::F32,
-            TokenKind::Keyword(Keyword::String) => Type::String,
-            TokenKind::Keyword(Keyword::Function) => Type::Function,
-            TokenKind::Keyword(Keyword::Unit) => Type::Unit,
-            TokenKind::Keyword(Keyword::List) => Type::List,
-            _ => return Err(Error::generic_utt(type_token)),
+        let ty = if self.optional(TokenKind::Separator(Separator::Colon)) {
+            self.parse_type()?
+        } else {
+            Type::Infer
         };
         let pos = id.position.clone();
         let ident_str = if let TokenKind::Identifier(ref s) = id.kind {
@@ -358,13 +1002,112 @@ impl Parser {
         } else {
             unreachable!();
         };
+        let default = if self.optional(TokenKind::Operator(Operator::Assign)) {
+            Some(self.parse_expression(0)?)
+        } else {
+            None
+        };
         Ok(Param {
             position: pos,
             param_type: ty,
             identifier: ident_str,
+            default,
         })
     }
 
+    // parses a type, e.g. i32, list<i32>, fn(i32, i32) -> i32
+    fn parse_type(&mut self) -> Result<Type, Error> {
+        let tok = self.expect(is_type)?;
+        match tok.kind {
+            // function type: fn(params) -> returns
+            TokenKind::Keyword(Keyword::Fn) => {
+                self.expect(|x| matches!(x, TokenKind::Separator(Separator::LParen)))?;
+                let mut params = Vec::new();
+                if !self.optional(TokenKind::Separator(Separator::RParen)) {
+                    loop {
+                        params.push(self.parse_type()?);
+                        if self.optional(TokenKind::Separator(Separator::Comma)) {
+                            continue;
+                        }
+                        self.expect(|x| matches!(x, TokenKind::Separator(Separator::RParen)))?;
+                        break;
+                    }
+                }
+                self.expect(|x| matches!(x, TokenKind::Separator(Separator::Arrow)))?;
+                let returns = self.parse_type()?;
+                Ok(Type::Function(Some(FunctionType {
+                    params,
+                    returns: Box::new(returns),
+                })))
+            }
+            // list type with optional element type: list<T>
+            TokenKind::Keyword(Keyword::List) => {
+                if !self.optional(TokenKind::Operator(Operator::Lt)) {
+                    return Ok(Type::List(None));
+                }
+                let element = self.parse_type()?;
+                self.expect_type_close()?;
+                Ok(Type::List(Some(Box::new(element))))
+            }
+            // option type with optional element type: option<T>
+            TokenKind::Keyword(Keyword::Option) => {
+                if !self.optional(TokenKind::Operator(Operator::Lt)) {
+                    return Ok(Type::Option(Box::new(Type::Infer)));
+                }
+                let element = self.parse_type()?;
+                self.expect_type_close()?;
+                Ok(Type::Option(Box::new(element)))
+            }
+            // result type with optional params: result<T, E>
+            TokenKind::Keyword(Keyword::Result) => {
+                if !self.optional(TokenKind::Operator(Operator::Lt)) {
+                    return Ok(Type::Result(Box::new(Type::Infer), Box::new(Type::Infer)));
+                }
+                let ok = self.parse_type()?;
+                self.expect(|x| matches!(x, TokenKind::Separator(Separator::Comma)))?;
+                let err = self.parse_type()?;
+                self.expect_type_close()?;
+                Ok(Type::Result(Box::new(ok), Box::new(err)))
+            }
+            // tuple type: (T, T, ...), () is unit and (T) is T
+            TokenKind::Separator(Separator::LParen) => {
+                let mut items = Vec::new();
+                if !self.optional(TokenKind::Separator(Separator::RParen)) {
+                    loop {
+                        items.push(self.parse_type()?);
+                        if self.optional(TokenKind::Separator(Separator::Comma)) {
+                            continue;
+                        }
+                        self.expect(|x| matches!(x, TokenKind::Separator(Separator::RParen)))?;
+                        break;
+                    }
+                }
+                Ok(match items.len() {
+                    0 => Type::Unit,
+                    1 => items.remove(0),
+                    _ => Type::Tuple(items),
+                })
+            }
+            // map type with optional params: map<K, V>
+            // `map` is not a keyword, so it still names functions and variables
+            TokenKind::Identifier(name) if name == "map" => {
+                if !self.optional(TokenKind::Operator(Operator::Lt)) {
+                    return Ok(Type::Map(Box::new(Type::Infer), Box::new(Type::Infer)));
+                }
+                let key = self.parse_type()?;
+                self.expect(|x| matches!(x, TokenKind::Separator(Separator::Comma)))?;
+                let value = self.parse_type()?;
+                self.expect_type_close()?;
+                Ok(Type::Map(Box::new(key), Box::new(value)))
+            }
+            // `_` is left to inference
+            TokenKind::Identifier(name) if name == "_" => Ok(Type::Infer),
+            // user-defined type
+            TokenKind::Identifier(name) => Ok(Type::Named(name)),
+            _ => get_type_from_keyword(tok),
+        }
+    }
+
     fn parse_if_expr(&mut self) -> Result<IfExp, Error> {
         let if_tok = self.expect(|x| matches!(x, TokenKind::Keyword(Keyword::If)))?;
         let pos = if_tok.position.clone();
@@ -374,23 +1117,182 @@ impl Parser {
         let mut res = IfExp {
             position: pos,
             if_cond: Box::new(cond),
-            then_branch: Box::new(self.parse_branch()?),
+            then_branch: self.parse_branch()?,
             else_branch: None,
         };
         if self.optional(TokenKind::Keyword(Keyword::Else)) {
             let else_branch = self.parse_branch()?;
-            res.else_branch = Some(Box::new(else_branch));
+            res.else_branch = Some(else_branch);
         }
         Ok(res)
     }
 
-    fn parse_branch(&mut self) -> Result<Statement, Error> {
-        let braces_present = self.optional(TokenKind::Separator(Separator::LBrace));
-        let branch = self.parse_statement()?;
-        if braces_present {
-            self.expect(|x| matches!(x, TokenKind::Separator(Separator::RBrace)))?;
+    fn parse_match_expr(&mut self) -> Result<MatchExp, Error> {
+        let match_tok = self.expect(|x| matches!(x, TokenKind::Keyword(Keyword::Match)))?;
+        // parens around the subject are optional (parsed as a paren expression)
+        let subject = self.parse_head()?;
+        self.expect(|x| matches!(x, TokenKind::Separator(Separator::LBrace)))?;
+        let mut arms = Vec::new();
+        // arms: pattern ["if" guard] "=>" branch, optionally separated by commas
+        while !self.optional(TokenKind::Separator(Separator::RBrace)) {
+            let pattern = self.parse_pattern()?;
+            let guard = if self.optional(TokenKind::Keyword(Keyword::If)) {
+                Some(self.parse_expression(0)?)
+            } else {
+                None
+            };
+            self.expect(|x| matches!(x, TokenKind::Separator(Separator::FatArrow)))?;
+            arms.push(MatchArm {
+                position: pattern.get_position().clone(),
+                pattern,
+                guard,
+                body: self.parse_branch()?,
+            });
+            self.optional(TokenKind::Separator(Separator::Comma));
+        }
+        Ok(MatchExp {
+            position: match_tok.position,
+            subject: Box::new(subject),
+            arms,
+        })
+    }
+
+    // cons patterns are right associative, e.g. a :: b :: rest
+    fn parse_pattern(&mut self) -> Result<Pattern, Error> {
+        let head = self.parse_simple_pattern()?;
+        if !self.optional(TokenKind::Cons) {
+            return Ok(head);
+        }
+        Ok(Pattern::Cons(ConsPattern {
+            position: head.get_position().clone(),
+            head: Box::new(head),
+            tail: Box::new(self.parse_pattern()?),
+        }))
+    }
+
+    fn parse_simple_pattern(&mut self) -> Result<Pattern, Error> {
+        let tok = self
+            .peek()
+            .ok_or_else(|| Error::generic_eof("expected a pattern"))?;
+        match tok.kind {
+            TokenKind::Identifier(name) if name == "_" => {
+                self.advance();
+                Ok(Pattern::Wildcard(tok.position))
+            }
+            TokenKind::Identifier(_) => {
+                let id = self.parse_identifier()?;
+                if !self.optional(TokenKind::Separator(Separator::LParen)) {
+                    return Ok(Pattern::Identifier(id));
+                }
+                // variant with payload, e.g. Rect(w, h)
+                let mut fields = Vec::new();
+                loop {
+                    fields.push(self.parse_pattern()?);
+                    if self.optional(TokenKind::Separator(Separator::Comma)) {
+                        continue;
+                    }
+                    self.expect(|x| matches!(x, TokenKind::Separator(Separator::RParen)))?;
+                    break;
+                }
+                Ok(Pattern::Variant(VariantPattern {
+                    position: id.position,
+                    name: id.name,
+                    fields,
+                }))
+            }
+            // negative numeric literal
+            TokenKind::Operator(Operator::Sub) => {
+                self.advance();
+                let mut lit = self.parse_literal_token()?;
+                lit.value = match lit.value {
+                    LiteralValue::Int(x) => LiteralValue::Int(-x),
+                    LiteralValue::Float(x) => LiteralValue::Float(-x),
+                    LiteralValue::Int64(x) => LiteralValue::Int64(-x),
+                    LiteralValue::Float64(x) => LiteralValue::Float64(-x),
+                    _ => return Err(Error::generic_utt(tok)),
+                };
+                lit.position = tok.position;
+                Ok(Pattern::Literal(lit))
+            }
+            TokenKind::Separator(Separator::LParen) => {
+                self.advance();
+                if self.optional(TokenKind::Separator(Separator::RParen)) {
+                    return Ok(Pattern::Literal(Literal {
+                        position: tok.position,
+                        value: LiteralValue::Unit,
+                    }));
+                }
+                // grouping, e.g. Some((h :: t))
+                let inner = self.parse_pattern()?;
+                if !self.optional(TokenKind::Separator(Separator::Comma)) {
+                    self.expect(|x| matches!(x, TokenKind::Separator(Separator::RParen)))?;
+                    return Ok(inner);
+                }
+                // tuple, e.g. (a, b)
+                let mut items = vec![inner];
+                loop {
+                    items.push(self.parse_pattern()?);
+                    if self.optional(TokenKind::Separator(Separator::Comma)) {
+                        continue;
+                    }
+                    self.expect(|x| matches!(x, TokenKind::Separator(Separator::RParen)))?;
+                    break;
+                }
+                Ok(Pattern::Tuple(TuplePattern {
+                    position: tok.position,
+                    items,
+                }))
+            }
+            // list of fixed length, e.g. [] or [a, b]
+            TokenKind::Separator(Separator::LBracket) => {
+                self.advance();
+                let mut items = Vec::new();
+                while !self.optional(TokenKind::Separator(Separator::RBracket)) {
+                    items.push(self.parse_pattern()?);
+                    if !self.optional(TokenKind::Separator(Separator::Comma)) {
+                        self.expect(|x| matches!(x, TokenKind::Separator(Separator::RBracket)))?;
+                        break;
+                    }
+                }
+                Ok(Pattern::List(ListPattern {
+                    position: tok.position,
+                    items,
+                }))
+            }
+            _ => Ok(Pattern::Literal(self.parse_literal_token()?)),
         }
-        Ok(branch)
+    }
+
+    // a block, or a single statement without braces
+    fn parse_branch(&mut self) -> Result<Block, Error> {
+        let tok = self
+            .peek()
+            .ok_or_else(|| Error::generic_eof("expected a branch"))?;
+        if tok.kind == TokenKind::Separator(Separator::LBrace) {
+            return self.parse_block();
+        }
+        Ok(Block {
+            position: tok.position,
+            statements: StatementList {
+                statements: vec![self.parse_statement()?],
+            },
+        })
+    }
+
+    // block: "{" statement* "}"
+    fn parse_block(&mut self) -> Result<Block, Error> {
+        let open = self.expect(|x| matches!(x, TokenKind::Separator(Separator::LBrace)))?;
+        let mut statements = Vec::new();
+        while !self.optional(TokenKind::Separator(Separator::RBrace)) {
+            if self.peek().is_none() {
+                return Err(Error::generic_eof("expected closing '}' in block"));
+            }
+            statements.push(self.parse_statement()?);
+        }
+        Ok(Block {
+            position: open.position,
+            statements: StatementList { statements },
+        })
     }
 
     fn parse_paren_expr(&mut self) -> Result<Expression, Error> {
@@ -403,8 +1305,24 @@ impl Parser {
             }));
         }
         let expr = self.parse_expression(0)?;
-        self.expect(|x| matches!(x, TokenKind::Separator(Separator::RParen)))?;
-        Ok(Expression::ParenExp(Box::new(expr)))
+        if !self.optional(TokenKind::Separator(Separator::Comma)) {
+            self.expect(|x| matches!(x, TokenKind::Separator(Separator::RParen)))?;
+            return Ok(Expression::ParenExp(Box::new(expr)));
+        }
+        // tuple literal, e.g. (1, "one")
+        let mut items = vec![expr];
+        loop {
+            items.push(self.parse_expression(0)?);
+            if self.optional(TokenKind::Separator(Separator::Comma)) {
+                continue;
+            }
+            self.expect(|x| matches!(x, TokenKind::Separator(Separator::RParen)))?;
+            break;
+        }
+        Ok(Expression::TupleExp(TupleExp {
+            position: left.position,
+            items,
+        }))
     }
 
     /*
@@ -415,10 +1333,34 @@ impl Parser {
         self.peek().is_some()
     }
 
+    // returns true if the next token starts a new line
+    fn on_new_line(&self) -> bool {
+        match (self.pos.checked_sub(1), self.peek()) {
+            (Some(prev), Some(tok)) => tok.position.line > self.tokens[prev].position.line,
+            _ => false,
+        }
+    }
+
     fn peek_n(&self, n: usize) -> Option<Token> {
         self.tokens.get(self.pos + n).cloned()
     }
 
+    // closes a type parameter list, splitting `>>` (e.g. list<list<i32>>)
+    fn expect_type_close(&mut self) -> Result<(), Error> {
+        if let Some(tok) = self.tokens.get_mut(self.pos)
+            && tok.kind == TokenKind::Operator(Operator::Shr)
+        {
+            // the token is split in place, so a parse restarted from a lookahead sees two
+            tok.kind = TokenKind::Operator(Operator::Gt);
+            tok.original = ">".to_string();
+            let mut second = tok.clone();
+            second.position.col += 1;
+            self.tokens.insert(self.pos + 1, second);
+        }
+        self.expect(|x| matches!(x, TokenKind::Operator(Operator::Gt)))?;
+        Ok(())
+    }
+
     fn peek(&self) -> Option<Token> {
         self.peek_n(0)
     }
@@ -463,15 +1405,47 @@ impl Parser {
     }
 }
 
+// `x |> f(a)` is the call `f(x, a)`, `x |> f(a, _)` fills the first placeholder: `f(a, x)`
+// anything but a call is called with `x` alone
+fn pipe(value: Expression, target: Expression) -> Call {
+    let arg = Argument {
+        position: value.get_position().clone(),
+        name: None,
+        value,
+    };
+    match target {
+        Expression::CallExp(mut call) => {
+            match call
+                .args
+                .iter()
+                .position(|a| matches!(a.value, Expression::Placeholder(_)))
+            {
+                Some(i) => call.args[i] = arg,
+                None => call.args.insert(0, arg),
+            }
+            call
+        }
+        target => Call {
+            position: target.get_position().clone(),
+            callee: Box::new(target),
+            args: vec![arg],
+        },
+    }
+}
+
 fn get_type_from_keyword(token: Token) -> Result<Type, Error> {
     let res = match token.kind {
         TokenKind::Keyword(Keyword::Bool) => Type::Bool,
         TokenKind::Keyword(Keyword::I32) => Type::I32,
         TokenKind::Keyword(Keyword::F32) => Type::F32,
+        TokenKind::Keyword(Keyword::I64) => Type::I64,
+        TokenKind::Keyword(Keyword::F64) => Type::F64,
+        TokenKind::Keyword(Keyword::BigInt) => Type::BigInt,
+        TokenKind::Keyword(Keyword::Char) => Type::Char,
         TokenKind::Keyword(Keyword::String) => Type::String,
-        TokenKind::Keyword(Keyword::Function) => Type::Function,
+        TokenKind::Keyword(Keyword::Function) => Type::Function(None),
         TokenKind::Keyword(Keyword::Unit) => Type::Unit,
-        TokenKind::Keyword(Keyword::List) => Type::List,
+        TokenKind::Keyword(Keyword::List) => Type::List(None),
         _ => {
             return Err(Error::new(
                 ErrorType::UnexpectedTokenType,
@@ -486,26 +1460,45 @@ fn get_type_from_keyword(token: Token) -> Result<Type, Error> {
 
 // returns true if token is a type
 fn is_type(token: &TokenKind) -> bool {
-    match token {
+    matches!(
+        token,
         TokenKind::Keyword(Keyword::Bool)
-        | TokenKind::Keyword(Keyword::I32)
-        | TokenKind::Keyword(Keyword::F32)
-        | TokenKind::Keyword(Keyword::String)
-        | TokenKind::Keyword(Keyword::Function)
-        | TokenKind::Keyword(Keyword::List)
-        | TokenKind::Keyword(Keyword::Unit) => true,
-        _ => false,
-    }
+            | TokenKind::Keyword(Keyword::I32)
+            | TokenKind::Keyword(Keyword::F32)
+            | TokenKind::Keyword(Keyword::I64)
+            | TokenKind::Keyword(Keyword::F64)
+            | TokenKind::Keyword(Keyword::BigInt)
+            | TokenKind::Keyword(Keyword::Char)
+            | TokenKind::Keyword(Keyword::String)
+            | TokenKind::Keyword(Keyword::Function)
+            | TokenKind::Keyword(Keyword::List)
+            | TokenKind::Keyword(Keyword::Option)
+            | TokenKind::Keyword(Keyword::Result)
+            | TokenKind::Keyword(Keyword::Unit)
+            | TokenKind::Keyword(Keyword::Fn)
+            | TokenKind::Identifier(_)
+            | TokenKind::Separator(Separator::LParen)
+    )
 }
 
+const UNARY_PRECEDENCE: u8 = 13;
+
 fn get_precedence(op: &Operator) -> u8 {
     match op {
         Operator::Or => 2,
         Operator::And => 3,
         Operator::Eq | Operator::Ne => 4,
         Operator::Lt | Operator::Le | Operator::Gt | Operator::Ge => 5,
-        Operator::Add | Operator::Sub => 6,
-        Operator::Mul | Operator::Div | Operator::Mod => 7,
+        // e.g. `xs |> length == 3` and `1 + 2 |> double`
+        Operator::Pipe => 6,
+        Operator::BitOr => 7,
+        Operator::BitXor => 8,
+        Operator::BitAnd => 9,
+        // also composition of functions
+        Operator::Shl | Operator::Shr => 10,
+        Operator::Add | Operator::Sub => 11,
+        Operator::Mul | Operator::Div | Operator::Mod => 12,
+        // not binary operators
         _ => 0,
     }
 }
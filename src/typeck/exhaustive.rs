@@ -0,0 +1,213 @@
+use crate::{parser::ast::TypeKey, typeck::ty::Ty};
+use std::collections::HashMap;
+
+/*
+* Exhaustiveness checking
+* */
+
+// a pattern reduced to what matters for coverage
+// bindings and `_` are both wildcards
+#[derive(Debug, Clone)]
+pub enum Pat {
+    Wild,
+    Ctor(Ctor, Vec<Pat>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Ctor {
+    Variant { enum_key: TypeKey, name: String },
+    Bool(bool),
+    Unit,
+    // empty list
+    Nil,
+    // non-empty list, fields are head and tail
+    Cons,
+    // tuple of the given length
+    Tuple(usize),
+    // literals of types with infinitely many values (i32, string, ...)
+    Literal(String),
+}
+
+impl Pat {
+    pub fn display(&self) -> String {
+        match self {
+            Pat::Wild => "_".to_string(),
+            Pat::Ctor(Ctor::Variant { name, .. }, fields) if fields.is_empty() => name.clone(),
+            Pat::Ctor(Ctor::Variant { name, .. }, fields) => {
+                let fields: Vec<String> = fields.iter().map(|f| f.display()).collect();
+                format!("{}({})", name, fields.join(", "))
+            }
+            Pat::Ctor(Ctor::Bool(b), _) => b.to_string(),
+            Pat::Ctor(Ctor::Unit, _) => "()".to_string(),
+            Pat::Ctor(Ctor::Nil, _) => "[]".to_string(),
+            Pat::Ctor(Ctor::Tuple(_), fields) => {
+                let fields: Vec<String> = fields.iter().map(|f| f.display()).collect();
+                format!("({})", fields.join(", "))
+            }
+            Pat::Ctor(Ctor::Cons, _) => {
+                // lists of known length are shown as [a, b]
+                let mut items = Vec::new();
+                let mut rest = self;
+                while let Pat::Ctor(Ctor::Cons, fields) = rest {
+                    items.push(fields[0].display());
+                    rest = &fields[1];
+                }
+                match rest {
+                    Pat::Ctor(Ctor::Nil, _) => format!("[{}]", items.join(", ")),
+                    _ => format!("{} :: {}", items.join(" :: "), rest.display()),
+                }
+            }
+            Pat::Ctor(Ctor::Literal(s), _) => s.clone(),
+        }
+    }
+}
+
+pub struct Coverage<'a> {
+    // variants of every declared enum, with their payload types
+    pub enums: &'a HashMap<TypeKey, Vec<(String, Vec<Ty>)>>,
+}
+
+impl Coverage<'_> {
+    // returns a value (as patterns, one per column) that no row matches
+    // every row must have one pattern per type in `tys`
+    pub fn missing(&self, rows: &[Vec<Pat>], tys: &[Ty]) -> Option<Vec<Pat>> {
+        if rows.is_empty() {
+            return Some(vec![Pat::Wild; tys.len()]);
+        }
+        let Some(ty) = tys.first() else {
+            // no columns left and a row remains
+            return None;
+        };
+        let column: Vec<&Pat> = rows.iter().map(|row| &row[0]).collect();
+        let ctors = self.signature(ty, &column).unwrap_or_default();
+        let used = |ctor: &Ctor| {
+            column
+                .iter()
+                .any(|pat| matches!(pat, Pat::Ctor(c, _) if c == ctor))
+        };
+        // some constructor never appears (or there are infinitely many)
+        // so only the rows starting with a wildcard can cover it
+        if ctors.is_empty() || !ctors.iter().all(|(ctor, _)| used(ctor)) {
+            let rest: Vec<Vec<Pat>> = rows
+                .iter()
+                .filter(|row| matches!(row[0], Pat::Wild))
+                .map(|row| row[1..].to_vec())
+                .collect();
+            let mut res = self.missing(&rest, &tys[1..])?;
+            let head = match ctors.into_iter().find(|(ctor, _)| !used(ctor)) {
+                Some((ctor, fields)) => Pat::Ctor(ctor, vec![Pat::Wild; fields.len()]),
+                None => Pat::Wild,
+            };
+            res.insert(0, head);
+            return Some(res);
+        }
+        for (ctor, fields) in ctors {
+            let arity = fields.len();
+            let rows = specialize(rows, &ctor, arity);
+            let tys: Vec<Ty> = fields.into_iter().chain(tys[1..].to_vec()).collect();
+            if let Some(mut res) = self.missing(&rows, &tys) {
+                let rest = res.split_off(arity);
+                let mut head = vec![Pat::Ctor(ctor, res)];
+                head.extend(rest);
+                return Some(head);
+            }
+        }
+        None
+    }
+
+    // all constructors of a type, None if they cannot be listed
+    fn signature(&self, ty: &Ty, column: &[&Pat]) -> Option<Vec<(Ctor, Vec<Ty>)>> {
+        match ty {
+            Ty::Bool => Some(vec![
+                (Ctor::Bool(true), Vec::new()),
+                (Ctor::Bool(false), Vec::new()),
+            ]),
+            Ty::Unit => Some(vec![(Ctor::Unit, Vec::new())]),
+            Ty::Tuple(items) => Some(vec![(Ctor::Tuple(items.len()), items.clone())]),
+            Ty::List(element) => Some(vec![
+                (Ctor::Nil, Vec::new()),
+                (Ctor::Cons, vec![*element.clone(), ty.clone()]),
+            ]),
+            Ty::Option(t) => Some(option_variants(t)),
+            Ty::Result(t, e) => Some(result_variants(t, e)),
+            Ty::Named(key) if self.enums.contains_key(key) => self.variants(key),
+            // the type is only known at runtime, use the patterns instead
+            Ty::Unknown | Ty::Var(_) => {
+                let ctor = column.iter().find_map(|pat| match pat {
+                    Pat::Ctor(ctor, _) => Some(ctor),
+                    Pat::Wild => None,
+                })?;
+                match ctor {
+                    Ctor::Variant { enum_key, .. } => self.variants(enum_key),
+                    Ctor::Bool(_) => self.signature(&Ty::Bool, column),
+                    Ctor::Unit => self.signature(&Ty::Unit, column),
+                    Ctor::Tuple(n) => Some(vec![(Ctor::Tuple(*n), vec![Ty::Unknown; *n])]),
+                    Ctor::Nil | Ctor::Cons => {
+                        self.signature(&Ty::List(Box::new(Ty::Unknown)), column)
+                    }
+                    Ctor::Literal(_) => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn variants(&self, enum_key: &TypeKey) -> Option<Vec<(Ctor, Vec<Ty>)>> {
+        match enum_key {
+            (None, name) if name == "option" => return Some(option_variants(&Ty::Unknown)),
+            (None, name) if name == "result" => {
+                return Some(result_variants(&Ty::Unknown, &Ty::Unknown));
+            }
+            _ => (),
+        }
+        let variants = self.enums.get(enum_key)?;
+        let res = variants
+            .iter()
+            .map(|(name, fields)| {
+                let ctor = Ctor::Variant {
+                    enum_key: enum_key.clone(),
+                    name: name.clone(),
+                };
+                (ctor, fields.clone())
+            })
+            .collect();
+        Some(res)
+    }
+}
+
+// options and results behave like enums named `option` and `result`
+pub fn builtin_ctor(enum_name: &str, name: &str) -> Ctor {
+    Ctor::Variant {
+        enum_key: (None, enum_name.to_string()),
+        name: name.to_string(),
+    }
+}
+
+fn option_variants(t: &Ty) -> Vec<(Ctor, Vec<Ty>)> {
+    vec![
+        (builtin_ctor("option", "none"), Vec::new()),
+        (builtin_ctor("option", "some"), vec![t.clone()]),
+    ]
+}
+
+fn result_variants(t: &Ty, e: &Ty) -> Vec<(Ctor, Vec<Ty>)> {
+    vec![
+        (builtin_ctor("result", "ok"), vec![t.clone()]),
+        (builtin_ctor("result", "err"), vec![e.clone()]),
+    ]
+}
+
+// keeps the rows that can match the constructor, expanding its fields into columns
+fn specialize(rows: &[Vec<Pat>], ctor: &Ctor, arity: usize) -> Vec<Vec<Pat>> {
+    let mut res = Vec::new();
+    for row in rows {
+        let mut fields = match &row[0] {
+            Pat::Wild => vec![Pat::Wild; arity],
+            Pat::Ctor(c, fields) if c == ctor => fields.clone(),
+            Pat::Ctor(..) => continue,
+        };
+        fields.extend_from_slice(&row[1..]);
+        res.push(fields);
+    }
+    res
+}
@@ -0,0 +1,210 @@
+use crate::typeck::ty::{FnTy, Scheme, Ty};
+use std::collections::HashMap;
+
+/*
+* Inference engine
+* */
+
+// substitution for inference variables (Hindley-Milner style)
+// Ty::Unknown unifies with everything and never binds a variable
+#[derive(Default)]
+pub struct Inference {
+    bindings: Vec<Option<Ty>>,
+}
+
+impl Inference {
+    pub fn new() -> Self {
+        Inference {
+            bindings: Vec::new(),
+        }
+    }
+
+    pub fn fresh(&mut self) -> Ty {
+        self.bindings.push(None);
+        Ty::Var(self.bindings.len() - 1)
+    }
+
+    // applies the substitution everywhere in the type
+    pub fn resolve(&self, ty: &Ty) -> Ty {
+        match ty {
+            Ty::Var(n) => match &self.bindings[*n] {
+                Some(t) => self.resolve(t),
+                None => ty.clone(),
+            },
+            Ty::List(t) => Ty::List(Box::new(self.resolve(t))),
+            Ty::Option(t) => Ty::Option(Box::new(self.resolve(t))),
+            Ty::Result(t, e) => Ty::Result(Box::new(self.resolve(t)), Box::new(self.resolve(e))),
+            Ty::Map(k, v) => Ty::Map(Box::new(self.resolve(k)), Box::new(self.resolve(v))),
+            Ty::Tuple(items) => Ty::Tuple(items.iter().map(|t| self.resolve(t)).collect()),
+            Ty::Function(Some(f)) => Ty::Function(Some(self.resolve_fn(f))),
+            Ty::Native(sig) => {
+                let mut sig = sig.clone();
+                sig.params = sig.params.iter().map(|p| self.resolve(p)).collect();
+                sig.returns = Box::new(self.resolve(&sig.returns));
+                Ty::Native(sig)
+            }
+            t => t.clone(),
+        }
+    }
+
+    fn resolve_fn(&self, f: &FnTy) -> FnTy {
+        FnTy {
+            params: f.params.iter().map(|p| self.resolve(p)).collect(),
+            returns: Box::new(self.resolve(&f.returns)),
+            names: f.names.clone(),
+            required: f.required,
+        }
+    }
+
+    // unifies two types, leaving the substitution untouched on failure
+    pub fn unify(&mut self, a: &Ty, b: &Ty) -> bool {
+        let snapshot = self.bindings.clone();
+        let res = self.unify_inner(a, b);
+        if !res {
+            self.bindings = snapshot;
+        }
+        res
+    }
+
+    fn unify_inner(&mut self, a: &Ty, b: &Ty) -> bool {
+        let a = self.shallow(a);
+        let b = self.shallow(b);
+        match (&a, &b) {
+            (Ty::Unknown, _) | (_, Ty::Unknown) => true,
+            (Ty::Var(x), Ty::Var(y)) if x == y => true,
+            (Ty::Var(n), t) | (t, Ty::Var(n)) => {
+                if self.occurs(*n, t) {
+                    return false;
+                }
+                self.bindings[*n] = Some(t.clone());
+                true
+            }
+            (Ty::List(x), Ty::List(y)) | (Ty::Option(x), Ty::Option(y)) => self.unify_inner(x, y),
+            (Ty::Result(x, e), Ty::Result(y, f)) | (Ty::Map(x, e), Ty::Map(y, f)) => {
+                self.unify_inner(x, y) && self.unify_inner(e, f)
+            }
+            (Ty::Tuple(x), Ty::Tuple(y)) => {
+                x.len() == y.len() && x.iter().zip(y).all(|(x, y)| self.unify_inner(x, y))
+            }
+            (Ty::Function(Some(f)), Ty::Function(Some(g))) => {
+                // params with a default can be left out, e.g. fn(i32, i32 = 1) fits fn(i32)
+                let (shorter, longer) = match f.params.len() <= g.params.len() {
+                    true => (f, g),
+                    false => (g, f),
+                };
+                shorter.params.len() >= longer.required
+                    && f.params
+                        .iter()
+                        .zip(&g.params)
+                        .all(|(x, y)| self.unify_inner(x, y))
+                    && self.unify_inner(&f.returns, &g.returns)
+            }
+            // plain functions accept any signature
+            (Ty::Function(None), Ty::Function(_) | Ty::Native(_))
+            | (Ty::Function(_) | Ty::Native(_), Ty::Function(None)) => true,
+            (Ty::Native(sig), Ty::Function(Some(f))) | (Ty::Function(Some(f)), Ty::Native(sig)) => {
+                if sig.variadic {
+                    return self.unify_inner(&sig.returns, &f.returns);
+                }
+                f.params.len() >= sig.required
+                    && f.params.len() <= sig.params.len()
+                    && f.params
+                        .iter()
+                        .zip(&sig.params)
+                        .all(|(x, y)| self.unify_inner(x, y))
+                    && self.unify_inner(&sig.returns, &f.returns)
+            }
+            (Ty::Native(_), Ty::Native(_)) => true,
+            (x, y) => x == y,
+        }
+    }
+
+    // resolves only the outermost variable
+    fn shallow(&self, ty: &Ty) -> Ty {
+        match ty {
+            Ty::Var(n) => match &self.bindings[*n] {
+                Some(t) => self.shallow(t),
+                None => ty.clone(),
+            },
+            t => t.clone(),
+        }
+    }
+
+    fn occurs(&self, n: usize, ty: &Ty) -> bool {
+        self.free_vars(ty).contains(&n)
+    }
+
+    pub fn free_vars(&self, ty: &Ty) -> Vec<usize> {
+        let mut res = Vec::new();
+        collect_vars(&self.resolve(ty), &mut res);
+        res
+    }
+
+    // quantifies the variables that are not bound in the environment
+    pub fn generalize(&self, ty: &Ty, env_vars: &[usize]) -> Scheme {
+        let vars = self
+            .free_vars(ty)
+            .into_iter()
+            .filter(|v| !env_vars.contains(v))
+            .collect();
+        Scheme {
+            vars,
+            ty: self.resolve(ty),
+        }
+    }
+
+    pub fn instantiate(&mut self, scheme: &Scheme) -> Ty {
+        if scheme.vars.is_empty() {
+            return scheme.ty.clone();
+        }
+        let fresh: HashMap<usize, Ty> = scheme.vars.iter().map(|v| (*v, self.fresh())).collect();
+        substitute(&self.resolve(&scheme.ty), &fresh)
+    }
+}
+
+fn collect_vars(ty: &Ty, res: &mut Vec<usize>) {
+    match ty {
+        Ty::Var(n) if !res.contains(n) => res.push(*n),
+        Ty::List(t) | Ty::Option(t) => collect_vars(t, res),
+        Ty::Result(t, e) | Ty::Map(t, e) => {
+            collect_vars(t, res);
+            collect_vars(e, res);
+        }
+        Ty::Tuple(items) => items.iter().for_each(|t| collect_vars(t, res)),
+        Ty::Function(Some(f)) => {
+            f.params.iter().for_each(|p| collect_vars(p, res));
+            collect_vars(&f.returns, res);
+        }
+        Ty::Native(sig) => {
+            sig.params.iter().for_each(|p| collect_vars(p, res));
+            collect_vars(&sig.returns, res);
+        }
+        _ => (),
+    }
+}
+
+fn substitute(ty: &Ty, vars: &HashMap<usize, Ty>) -> Ty {
+    match ty {
+        Ty::Var(n) => vars.get(n).cloned().unwrap_or(Ty::Var(*n)),
+        Ty::List(t) => Ty::List(Box::new(substitute(t, vars))),
+        Ty::Option(t) => Ty::Option(Box::new(substitute(t, vars))),
+        Ty::Result(t, e) => {
+            Ty::Result(Box::new(substitute(t, vars)), Box::new(substitute(e, vars)))
+        }
+        Ty::Map(k, v) => Ty::Map(Box::new(substitute(k, vars)), Box::new(substitute(v, vars))),
+        Ty::Tuple(items) => Ty::Tuple(items.iter().map(|t| substitute(t, vars)).collect()),
+        Ty::Function(Some(f)) => Ty::Function(Some(FnTy {
+            params: f.params.iter().map(|p| substitute(p, vars)).collect(),
+            returns: Box::new(substitute(&f.returns, vars)),
+            names: f.names.clone(),
+            required: f.required,
+        })),
+        Ty::Native(sig) => {
+            let mut sig = sig.clone();
+            sig.params = sig.params.iter().map(|p| substitute(p, vars)).collect();
+            sig.returns = Box::new(substitute(&sig.returns, vars));
+            Ty::Native(sig)
+        }
+        t => t.clone(),
+    }
+}
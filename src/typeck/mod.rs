@@ -0,0 +1,1537 @@
+use crate::{
+    loader::Module,
+    parser::ast::*,
+    typeck::{
+        exhaustive::{Coverage, Ctor, Pat, builtin_ctor},
+        infer::Inference,
+        ty::{FnTy, Scheme, Signature, Ty},
+    },
+    utils::{
+        lang_error::{Error, ErrorType},
+        options::Options,
+        position::Position,
+    },
+};
+use std::collections::{HashMap, HashSet};
+
+pub mod exhaustive;
+pub mod infer;
+pub mod ty;
+
+/*
+* Type checker
+* */
+
+// checks the whole program without running it
+// every violation is collected instead of stopping at the first one
+// inferred types (`let`, unannotated params) are written back into the AST
+pub fn check(ast: &mut StatementList) -> Result<(), Vec<Error>> {
+    check_with(ast, &Options::default())
+}
+
+pub fn check_with(ast: &mut StatementList, options: &Options) -> Result<(), Vec<Error>> {
+    let mut checker = Checker::new();
+    checker.strict = options.strict;
+    checker.check_module(ast);
+    if checker.errors.is_empty() {
+        return Ok(());
+    }
+    Err(checker.errors)
+}
+
+// checks the files of a program in the order they were loaded (see loader::load)
+// errors in imported files name the file
+pub fn check_modules(modules: &mut [Module], options: &Options) -> Result<(), Vec<Error>> {
+    let mut checker = Checker::new();
+    checker.strict = options.strict;
+    let entry = modules.len().saturating_sub(1);
+    for (i, module) in modules.iter_mut().enumerate() {
+        let start = checker.errors.len();
+        checker.file = module.path.clone();
+        checker.module = Some(module.id.clone());
+        let exports = checker.check_module(&mut module.ast);
+        if i != entry {
+            for e in &mut checker.errors[start..] {
+                e.file.get_or_insert_with(|| module.path.clone());
+            }
+        }
+        checker.modules.insert(module.id.clone(), exports);
+        let types = module.ast.exported_types();
+        checker.exported_types.insert(module.id.clone(), types);
+    }
+    if checker.errors.is_empty() {
+        return Ok(());
+    }
+    Err(checker.errors)
+}
+
+struct Checker {
+    scopes: Vec<HashMap<String, Scheme>>,
+    // return types of the enclosing functions
+    returns: Vec<Ty>,
+    // declared structs, by module and name
+    structs: HashMap<TypeKey, Vec<(String, Ty)>>,
+    // declared enums, by module and name (variants with their payload types)
+    enums: HashMap<TypeKey, Vec<(String, Vec<Ty>)>>,
+    // structs and enums the current file can name, its own and the ones it imports by name
+    visible: HashMap<String, TypeKey>,
+    // path of the file being checked
+    file: String,
+    // id of the file being checked, None for a program that was not loaded from files
+    module: Option<String>,
+    // exports of the files checked so far, by module id
+    modules: HashMap<String, HashMap<String, Scheme>>,
+    // exported structs and enums of the files checked so far, by module id
+    exported_types: HashMap<String, Vec<String>>,
+    // mutable bindings, by scope depth and name
+    mutables: HashSet<(usize, String)>,
+    // number of loops around the current statement (reset inside functions)
+    loops: usize,
+    // implicit numeric promotion is an error
+    strict: bool,
+    inference: Inference,
+    errors: Vec<Error>,
+}
+
+impl Checker {
+    fn new() -> Self {
+        let mut inference = Inference::new();
+        Checker {
+            scopes: vec![ty::get_stdlib_types(&mut inference)],
+            returns: Vec::new(),
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            visible: HashMap::new(),
+            file: String::new(),
+            module: None,
+            modules: HashMap::new(),
+            exported_types: HashMap::new(),
+            mutables: HashSet::new(),
+            loops: 0,
+            strict: false,
+            inference,
+            errors: Vec::new(),
+        }
+    }
+
+    // every file has its own top-level scope, returns what the file exports
+    fn check_module(&mut self, ast: &mut StatementList) -> HashMap<String, Scheme> {
+        self.scopes.push(HashMap::new());
+        self.visible.clear();
+        self.check_statements(&mut ast.statements);
+        let scope = self.scopes.pop().expect("missing scope");
+        let depth = self.scopes.len();
+        self.mutables.retain(|(d, _)| *d < depth);
+        ast.exports()
+            .into_iter()
+            .filter_map(|name| Some((name.clone(), scope.get(&name)?.clone())))
+            .collect()
+    }
+
+    fn check_statements(&mut self, statements: &mut [Statement]) -> Ty {
+        // structs can be used before their declaration (e.g. in function bodies)
+        // all of them are named first, so their fields can refer to each other
+        for stmt in statements.iter_mut() {
+            if let Statement::Struct(StructDef {
+                name,
+                position,
+                module,
+                ..
+            })
+            | Statement::Enum(EnumDef {
+                name,
+                position,
+                module,
+                ..
+            }) = stmt
+            {
+                self.declare_type(name, position);
+                *module = self.module.clone();
+            }
+        }
+        for stmt in statements.iter() {
+            if let Statement::Struct(def) = stmt {
+                let fields = def
+                    .fields
+                    .iter()
+                    .map(|f| {
+                        (
+                            f.identifier.clone(),
+                            Ty::from_type(&f.field_type, &mut self.inference, &self.visible),
+                        )
+                    })
+                    .collect();
+                self.structs
+                    .insert((def.module.clone(), def.name.clone()), fields);
+            }
+            if let Statement::Enum(def) = stmt {
+                let variants = def
+                    .variants
+                    .iter()
+                    .map(|v| {
+                        let fields = v
+                            .fields
+                            .iter()
+                            .map(|f| Ty::from_type(f, &mut self.inference, &self.visible))
+                            .collect();
+                        (v.name.clone(), fields)
+                    })
+                    .collect();
+                self.enums
+                    .insert((def.module.clone(), def.name.clone()), variants);
+            }
+        }
+        let mut last = Ty::Unit;
+        for stmt in statements {
+            last = self.check_statement(stmt);
+        }
+        last
+    }
+
+    fn check_statement(&mut self, stmt: &mut Statement) -> Ty {
+        match stmt {
+            Statement::Assignment(a) => self.check_assignment(a),
+            Statement::Destructure(d) => self.check_destructure(d),
+            Statement::Reassignment(r) => self.check_reassignment(r),
+            Statement::While(w) => self.check_while(w),
+            Statement::For(f) => self.check_for(f),
+            Statement::Break(position) | Statement::Continue(position) => {
+                if self.loops == 0 {
+                    let position = position.clone();
+                    let keyword = match stmt {
+                        Statement::Break(_) => "break",
+                        _ => "continue",
+                    };
+                    self.error(
+                        ErrorType::InvalidBreakLocation,
+                        &position,
+                        keyword,
+                        "break and continue must be inside a loop",
+                    );
+                }
+                // like a return, it fits any surrounding expression
+                self.inference.fresh()
+            }
+            Statement::Expression(e) => self.check_expression(e),
+            Statement::Return(r) => self.check_return(r),
+            Statement::Struct(s) => self.check_struct(s),
+            Statement::Enum(e) => self.check_enum(e),
+            Statement::Import(i) => self.check_import(i),
+        }
+    }
+
+    // makes a struct or enum nameable in the current file
+    // a name can only refer to one type in a file, imported or declared
+    fn name_type(&mut self, name: &str, key: TypeKey, position: &Position) {
+        match self.visible.get(name) {
+            Some(other) if *other != key => {
+                let message = if other.0 == self.module {
+                    "a type with this name is declared in this file"
+                } else {
+                    "a type with this name is already imported"
+                };
+                self.error(ErrorType::InvalidSymbol, position, name, message);
+            }
+            _ => {
+                self.visible.insert(name.to_string(), key);
+            }
+        }
+    }
+
+    fn declare_type(&mut self, name: &str, position: &Position) {
+        let key = (self.module.clone(), name.to_string());
+        self.name_type(name, key, position);
+    }
+
+    fn check_import(&mut self, import: &Import) -> Ty {
+        let exports = import
+            .module
+            .as_ref()
+            .and_then(|id| Some((id.clone(), self.modules.get(id)?.clone())));
+        let Some((id, exports)) = exports else {
+            self.error(
+                ErrorType::InvalidImport,
+                &import.position,
+                &import.path,
+                "imports are resolved when a program is loaded from a file",
+            );
+            return Ty::Unit;
+        };
+        match &import.names {
+            ImportNames::Namespace(alias) => self.bind(alias, Scheme::mono(Ty::Module(id))),
+            ImportNames::Selected(names) => {
+                let types = self.exported_types.get(&id).cloned().unwrap_or_default();
+                for name in names {
+                    let is_type = types.contains(&name.name);
+                    if is_type {
+                        let key = (Some(id.clone()), name.name.clone());
+                        self.name_type(&name.name, key, &name.position);
+                    }
+                    match exports.get(&name.name) {
+                        Some(scheme) => self.bind(&name.name, scheme.clone()),
+                        None if is_type => (),
+                        None => {
+                            let message = format!("\"{}\" does not export it", import.path);
+                            self.error(
+                                ErrorType::InvalidImport,
+                                &name.position,
+                                &name.name,
+                                &message,
+                            );
+                            self.bind(&name.name, Scheme::mono(Ty::Unknown));
+                        }
+                    }
+                }
+            }
+        }
+        Ty::Unit
+    }
+
+    fn check_enum(&mut self, def: &EnumDef) -> Ty {
+        let returns = Ty::Named((def.module.clone(), def.name.clone()));
+        for (i, variant) in def.variants.iter().enumerate() {
+            for field in &variant.fields {
+                self.check_annotation(field, &variant.position);
+            }
+            if def.variants[..i].iter().any(|v| v.name == variant.name) {
+                self.error(
+                    ErrorType::InvalidSymbol,
+                    &variant.position,
+                    &variant.name,
+                    "variant declared more than once",
+                );
+            }
+            // variants are bound as values, those with a payload as constructors
+            let ty = if variant.fields.is_empty() {
+                returns.clone()
+            } else {
+                let params = variant
+                    .fields
+                    .iter()
+                    .map(|f| Ty::from_type(f, &mut self.inference, &self.visible))
+                    .collect();
+                Ty::Function(Some(FnTy::new(params, returns.clone())))
+            };
+            self.bind(&variant.name, Scheme::mono(ty));
+        }
+        Ty::Unit
+    }
+
+    fn check_struct(&mut self, def: &StructDef) -> Ty {
+        for (i, field) in def.fields.iter().enumerate() {
+            self.check_annotation(&field.field_type, &field.position);
+            if def.fields[..i]
+                .iter()
+                .any(|f| f.identifier == field.identifier)
+            {
+                self.error(
+                    ErrorType::InvalidField,
+                    &field.position,
+                    &field.identifier,
+                    "field declared more than once",
+                );
+            }
+        }
+        Ty::Unit
+    }
+
+    fn check_assignment(&mut self, a: &mut Assignment) -> Ty {
+        self.check_annotation(&a.assignment_type, &a.position);
+        let declared = Ty::from_type(&a.assignment_type, &mut self.inference, &self.visible);
+        // functions are bound before the rhs is checked (enables recursion)
+        // with their param names and defaults, so recursive calls can use them too
+        let recursive = match &a.expression {
+            Expression::FunctionExp(f) => {
+                let own = Ty::Function(Some(FnTy {
+                    params: f.params.iter().map(|_| self.inference.fresh()).collect(),
+                    returns: Box::new(self.inference.fresh()),
+                    names: f.params.iter().map(|p| p.identifier.clone()).collect(),
+                    required: f.required(),
+                }));
+                // a mismatch with the declared type is reported below
+                self.inference.unify(&own, &declared);
+                self.bind(&a.identifier, Scheme::mono(own.clone()));
+                Some(own)
+            }
+            _ => None,
+        };
+        let found = self.check_expression(&mut a.expression);
+        let found = self.widen(&a.position, &declared, found);
+        if let Some(own) = &recursive {
+            self.expect(&a.position, own, &found, "invalid recursive use");
+        }
+        let matches = self.expect(&a.position, &declared, &found, "invalid assignment type");
+        // keep the more precise type (e.g. a signature for a plain function)
+        let bound = match (&declared, self.inference.resolve(&found)) {
+            (_, Ty::Unknown) => declared,
+            (Ty::Function(None) | Ty::List(_), found) if matches => found,
+            _ => declared,
+        };
+        // generalize over everything not fixed by the enclosing scopes
+        self.scopes
+            .last_mut()
+            .expect("missing scope")
+            .remove(&a.identifier);
+        // mutable bindings keep one type for every value they hold
+        let scheme = if a.mutable {
+            Scheme::mono(self.inference.resolve(&bound))
+        } else {
+            self.inference.generalize(&bound, &self.env_vars())
+        };
+        if a.assignment_type == Type::Infer {
+            a.assignment_type = scheme.ty.to_type();
+        }
+        self.bind(&a.identifier, scheme);
+        if a.mutable {
+            let depth = self.scopes.len() - 1;
+            self.mutables.insert((depth, a.identifier.clone()));
+        }
+        Ty::Unit
+    }
+
+    fn check_while(&mut self, w: &mut WhileLoop) -> Ty {
+        let found = self.check_expression(&mut w.cond);
+        let position = w.cond.get_position().clone();
+        self.expect(
+            &position,
+            &Ty::Bool,
+            &found,
+            "loop condition must be a bool",
+        );
+        self.check_loop_body(&mut w.body, None);
+        Ty::Unit
+    }
+
+    fn check_for(&mut self, f: &mut ForLoop) -> Ty {
+        let found = self.check_expression(&mut f.iterable);
+        let element = match self.inference.resolve(&found) {
+            Ty::List(t) => *t,
+            Ty::Var(_) => {
+                let element = self.inference.fresh();
+                self.inference
+                    .unify(&found, &Ty::List(Box::new(element.clone())));
+                element
+            }
+            Ty::Unknown => Ty::Unknown,
+            other => {
+                self.error(
+                    ErrorType::InvalidOperand,
+                    &f.position,
+                    other.display(),
+                    "for loops iterate over a list",
+                );
+                Ty::Unknown
+            }
+        };
+        self.check_loop_body(&mut f.body, Some((&f.identifier, element)));
+        Ty::Unit
+    }
+
+    // the body has its own scope, with the loop variable if there is one
+    fn check_loop_body(&mut self, body: &mut StatementList, var: Option<(&str, Ty)>) {
+        self.scopes.push(HashMap::new());
+        if let Some((name, ty)) = var {
+            self.bind(name, Scheme::mono(ty));
+        }
+        self.loops += 1;
+        self.check_statements(&mut body.statements);
+        self.loops -= 1;
+        self.scopes.pop();
+    }
+
+    fn check_reassignment(&mut self, r: &mut Reassignment) -> Ty {
+        let found = self.check_expression(&mut r.expression);
+        let binding = self
+            .scopes
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(depth, scope)| Some((depth, scope.get(&r.identifier)?.clone())));
+        match binding {
+            None => self.error(
+                ErrorType::InvalidSymbol,
+                &r.position,
+                &r.identifier,
+                "assignment to an undeclared variable",
+            ),
+            Some((depth, _)) if !self.mutables.contains(&(depth, r.identifier.clone())) => self
+                .error(
+                    ErrorType::ImmutableAssignment,
+                    &r.position,
+                    &r.identifier,
+                    "cannot assign twice to an immutable binding",
+                ),
+            Some((_, scheme)) => {
+                let found = self.widen(&r.position, &scheme.ty, found);
+                self.expect(&r.position, &scheme.ty, &found, "invalid assignment type");
+            }
+        }
+        Ty::Unit
+    }
+
+    fn check_destructure(&mut self, d: &mut Destructure) -> Ty {
+        let mut targets = Vec::new();
+        for target in &d.targets {
+            self.check_annotation(&target.target_type, &target.position);
+            targets.push(Ty::from_type(
+                &target.target_type,
+                &mut self.inference,
+                &self.visible,
+            ));
+        }
+        let found = self.check_expression(&mut d.expression);
+        let expected = Ty::Tuple(targets.clone());
+        self.expect(
+            &d.position,
+            &expected,
+            &found,
+            "invalid destructuring assignment",
+        );
+        let env_vars = self.env_vars();
+        for (target, ty) in d.targets.iter_mut().zip(&targets) {
+            let scheme = self.inference.generalize(ty, &env_vars);
+            if target.target_type == Type::Infer {
+                target.target_type = scheme.ty.to_type();
+            }
+            self.bind(&target.identifier, scheme);
+        }
+        Ty::Unit
+    }
+
+    fn check_return(&mut self, r: &mut Return) -> Ty {
+        let found = self.check_expression(&mut r.expression);
+        let position = r.expression.get_position().clone();
+        let expected = match self.returns.last() {
+            Some(t) => t.clone(),
+            None => {
+                self.error(
+                    ErrorType::InvalidReturnLocation,
+                    &position,
+                    "return",
+                    "return must be inside a function",
+                );
+                return self.inference.fresh();
+            }
+        };
+        self.expect(&position, &expected, &found, "function returns wrong type");
+        // a return never produces a value, so it fits any surrounding expression
+        self.inference.fresh()
+    }
+
+    fn check_expression(&mut self, expression: &mut Expression) -> Ty {
+        match expression {
+            // only parsed as a call argument, takes the type of its param (see check_call)
+            Expression::Placeholder(_) => self.inference.fresh(),
+            Expression::LiteralExp(lit) => match lit.value {
+                LiteralValue::Unit => Ty::Unit,
+                LiteralValue::Int(_) => Ty::I32,
+                LiteralValue::Float(_) => Ty::F32,
+                LiteralValue::Int64(_) => Ty::I64,
+                LiteralValue::Float64(_) => Ty::F64,
+                LiteralValue::Bool(_) => Ty::Bool,
+                LiteralValue::Char(_) => Ty::Char,
+                LiteralValue::String(_) => Ty::String,
+            },
+            Expression::IdentifierExp(id) => self.lookup(id),
+            Expression::FunctionExp(f) => self.check_function(f),
+            Expression::CallExp(c) => self.check_call(c),
+            Expression::ParenExp(inner) => self.check_expression(inner),
+            Expression::BinaryExp(b) => self.check_binary(b),
+            Expression::IfExp(i) => self.check_if(i),
+            Expression::ConsExp(c) => self.check_cons(c),
+            Expression::ListExp(l) => self.check_list(l),
+            Expression::MapExp(m) => self.check_map(m),
+            Expression::RecordExp(r) => self.check_record(r),
+            Expression::FieldExp(f) => self.check_field(f),
+            Expression::MatchExp(m) => self.check_match(m),
+            Expression::TupleExp(t) => Ty::Tuple(
+                t.items
+                    .iter_mut()
+                    .map(|item| self.check_expression(item))
+                    .collect(),
+            ),
+            Expression::TupleIndexExp(t) => self.check_tuple_index(t),
+            Expression::TryExp(t) => self.check_try(t),
+            Expression::UnaryExp(u) => self.check_unary(u),
+            Expression::BlockExp(b) => self.check_block(&mut b.statements),
+        }
+    }
+
+    fn check_match(&mut self, exp: &mut MatchExp) -> Ty {
+        let subject = self.check_expression(&mut exp.subject);
+        let res = self.inference.fresh();
+        let mut same = true;
+        let mut rows = Vec::new();
+        for arm in exp.arms.iter_mut() {
+            // bindings from the pattern are only visible in the arm
+            self.scopes.push(HashMap::new());
+            let pat = self.check_pattern(&mut arm.pattern, &subject);
+            match &mut arm.guard {
+                Some(guard) => {
+                    let found = self.check_expression(guard);
+                    let position = guard.get_position().clone();
+                    self.expect(&position, &Ty::Bool, &found, "match guard must be a bool");
+                }
+                // guarded arms may not match, so they do not count towards coverage
+                None => rows.push(vec![pat]),
+            }
+            let found = self.check_block(&mut arm.body.statements);
+            self.scopes.pop();
+            // arms with different types are only known at runtime
+            same = same && self.inference.unify(&res, &found);
+        }
+        let subject = self.inference.resolve(&subject);
+        let coverage = Coverage { enums: &self.enums };
+        if let Some(missing) = coverage.missing(&rows, &[subject]) {
+            self.error(
+                ErrorType::NonExhaustiveMatch,
+                &exp.position,
+                missing[0].display(),
+                "match does not cover every case",
+            );
+        }
+        if same {
+            self.inference.resolve(&res)
+        } else {
+            Ty::Unknown
+        }
+    }
+
+    // checks a pattern against the matched type, binding its variables
+    fn check_pattern(&mut self, pattern: &mut Pattern, expected: &Ty) -> Pat {
+        match pattern {
+            Pattern::Wildcard(_) => Pat::Wild,
+            Pattern::Identifier(id) if id.name == "none" => {
+                let found = Ty::Option(Box::new(self.inference.fresh()));
+                self.expect(&id.position, expected, &found, "invalid pattern type");
+                Pat::Ctor(builtin_ctor("option", "none"), Vec::new())
+            }
+            Pattern::Variant(p) if p.fields.len() == 1 && is_builtin_variant(&p.name) => {
+                let (field, found, enum_name) = match p.name.as_str() {
+                    "some" => {
+                        let t = self.inference.fresh();
+                        (t.clone(), Ty::Option(Box::new(t)), "option")
+                    }
+                    "ok" => {
+                        let t = self.inference.fresh();
+                        let e = Box::new(self.inference.fresh());
+                        (t.clone(), Ty::Result(Box::new(t), e), "result")
+                    }
+                    _ => {
+                        let e = self.inference.fresh();
+                        let t = Box::new(self.inference.fresh());
+                        (e.clone(), Ty::Result(t, Box::new(e)), "result")
+                    }
+                };
+                if !self.expect(&p.position, expected, &found, "invalid pattern type") {
+                    self.bind_unknown(&mut p.fields);
+                    return Pat::Wild;
+                }
+                let field = self.check_pattern(&mut p.fields[0], &field);
+                Pat::Ctor(builtin_ctor(enum_name, &p.name), vec![field])
+            }
+            Pattern::Identifier(id) => match self.lookup_variant(&id.name, expected) {
+                Some((enum_key, fields)) if fields.is_empty() => {
+                    let found = Ty::Named(enum_key.clone());
+                    self.expect(&id.position, expected, &found, "invalid pattern type");
+                    let ctor = Ctor::Variant {
+                        enum_key,
+                        name: id.name.clone(),
+                    };
+                    // the interpreter cannot tell variants of other files from bindings
+                    *pattern = Pattern::Variant(VariantPattern {
+                        position: id.position.clone(),
+                        name: id.name.clone(),
+                        fields: Vec::new(),
+                    });
+                    Pat::Ctor(ctor, Vec::new())
+                }
+                _ => {
+                    self.bind(&id.name, Scheme::mono(expected.clone()));
+                    Pat::Wild
+                }
+            },
+            Pattern::Literal(lit) => {
+                let (found, ctor) = match &lit.value {
+                    LiteralValue::Unit => (Ty::Unit, Ctor::Unit),
+                    LiteralValue::Bool(b) => (Ty::Bool, Ctor::Bool(*b)),
+                    LiteralValue::Int(x) => (Ty::I32, Ctor::Literal(x.to_string())),
+                    LiteralValue::Float(x) => (Ty::F32, Ctor::Literal(x.to_string())),
+                    LiteralValue::Int64(x) => (Ty::I64, Ctor::Literal(x.to_string())),
+                    LiteralValue::Float64(x) => (Ty::F64, Ctor::Literal(x.to_string())),
+                    LiteralValue::Char(c) => (Ty::Char, Ctor::Literal(format!("{:?}", c))),
+                    LiteralValue::String(s) => (Ty::String, Ctor::Literal(format!("{:?}", s))),
+                };
+                self.expect(&lit.position, expected, &found, "invalid pattern type");
+                Pat::Ctor(ctor, Vec::new())
+            }
+            Pattern::Tuple(p) => {
+                let items: Vec<Ty> = p.items.iter().map(|_| self.inference.fresh()).collect();
+                let tuple = Ty::Tuple(items.clone());
+                if !self.expect(&p.position, expected, &tuple, "invalid pattern type") {
+                    self.bind_unknown(&mut p.items);
+                    return Pat::Wild;
+                }
+                let fields = p
+                    .items
+                    .iter_mut()
+                    .zip(&items)
+                    .map(|(pattern, ty)| self.check_pattern(pattern, ty))
+                    .collect();
+                Pat::Ctor(Ctor::Tuple(p.items.len()), fields)
+            }
+            Pattern::List(p) => {
+                let element = self.list_element(expected, &p.position);
+                self.check_list_pattern(&mut p.items, &element)
+            }
+            Pattern::Cons(p) => {
+                let element = self.list_element(expected, &p.position);
+                let head = self.check_pattern(&mut p.head, &element);
+                let tail = self.check_pattern(&mut p.tail, &Ty::List(Box::new(element)));
+                Pat::Ctor(Ctor::Cons, vec![head, tail])
+            }
+            Pattern::Variant(p) => {
+                let Some((enum_key, fields)) = self.lookup_variant(&p.name, expected) else {
+                    self.error(
+                        ErrorType::InvalidSymbol,
+                        &p.position,
+                        &p.name,
+                        "unknown variant",
+                    );
+                    self.bind_unknown(&mut p.fields);
+                    return Pat::Wild;
+                };
+                let found = Ty::Named(enum_key.clone());
+                self.expect(&p.position, expected, &found, "invalid pattern type");
+                if fields.len() != p.fields.len() {
+                    self.error(
+                        ErrorType::InvalidParams,
+                        &p.position,
+                        format!("found: {:?}, expected {:?}", p.fields.len(), fields.len()),
+                        "incorrect number of fields in pattern",
+                    );
+                    self.bind_unknown(&mut p.fields);
+                    return Pat::Wild;
+                }
+                let fields = p
+                    .fields
+                    .iter_mut()
+                    .zip(&fields)
+                    .map(|(pattern, ty)| self.check_pattern(pattern, ty))
+                    .collect();
+                let ctor = Ctor::Variant {
+                    enum_key,
+                    name: p.name.clone(),
+                };
+                Pat::Ctor(ctor, fields)
+            }
+        }
+    }
+
+    // binds the variables of invalid patterns, so their uses are not reported too
+    fn bind_unknown(&mut self, patterns: &mut [Pattern]) {
+        for pattern in patterns {
+            self.check_pattern(pattern, &Ty::Unknown);
+        }
+    }
+
+    // the element type of a list matched by a pattern
+    fn list_element(&mut self, expected: &Ty, position: &Position) -> Ty {
+        let element = self.inference.fresh();
+        let list = Ty::List(Box::new(element.clone()));
+        if self.expect(position, expected, &list, "invalid pattern type") {
+            element
+        } else {
+            Ty::Unknown
+        }
+    }
+
+    fn check_list_pattern(&mut self, items: &mut [Pattern], element: &Ty) -> Pat {
+        let Some((first, rest)) = items.split_first_mut() else {
+            return Pat::Ctor(Ctor::Nil, Vec::new());
+        };
+        let head = self.check_pattern(first, element);
+        let tail = self.check_list_pattern(rest, element);
+        Pat::Ctor(Ctor::Cons, vec![head, tail])
+    }
+
+    // finds the enum declaring a variant, preferring the expected enum
+    fn lookup_variant(&self, name: &str, expected: &Ty) -> Option<(TypeKey, Vec<Ty>)> {
+        let find = |enum_key: &TypeKey| {
+            let variants = self.enums.get(enum_key)?;
+            let (_, fields) = variants.iter().find(|(v, _)| v == name)?;
+            Some((enum_key.clone(), fields.clone()))
+        };
+        if let Ty::Named(enum_key) = self.inference.resolve(expected)
+            && let Some(res) = find(&enum_key)
+        {
+            return Some(res);
+        }
+        let mut visible: Vec<(&String, &TypeKey)> = self.visible.iter().collect();
+        visible.sort();
+        visible.into_iter().find_map(|(_, key)| find(key))
+    }
+
+    fn check_record(&mut self, exp: &mut RecordExp) -> Ty {
+        // structs of other files can only be named once they are imported
+        let found = self
+            .visible
+            .get(&exp.name)
+            .and_then(|key| Some((key.clone(), self.structs.get(key)?.clone())));
+        let (key, fields) = match found {
+            Some(found) => found,
+            None => {
+                exp.fields.iter_mut().for_each(|f| {
+                    self.check_expression(&mut f.value);
+                });
+                self.error(
+                    ErrorType::InvalidSymbol,
+                    &exp.position,
+                    &exp.name,
+                    "unknown struct",
+                );
+                return Ty::Unknown;
+            }
+        };
+        for i in 0..exp.fields.len() {
+            let found = self.check_expression(&mut exp.fields[i].value);
+            let init = &exp.fields[i];
+            let position = init.position.clone();
+            let message = match fields.iter().find(|(name, _)| *name == init.identifier) {
+                None => "struct has no such field",
+                Some(_)
+                    if exp.fields[..i]
+                        .iter()
+                        .any(|f| f.identifier == init.identifier) =>
+                {
+                    "field initialized more than once"
+                }
+                Some((_, expected)) => {
+                    self.expect(&position, expected, &found, "invalid field type");
+                    continue;
+                }
+            };
+            let identifier = init.identifier.clone();
+            self.error(ErrorType::InvalidField, &position, identifier, message);
+        }
+        for (name, _) in &fields {
+            if !exp.fields.iter().any(|f| f.identifier == *name) {
+                self.error(
+                    ErrorType::InvalidField,
+                    &exp.position,
+                    name,
+                    "missing field in record literal",
+                );
+            }
+        }
+        exp.module = key.0.clone();
+        Ty::Named(key)
+    }
+
+    fn check_tuple_index(&mut self, exp: &mut TupleIndexExp) -> Ty {
+        let tuple = self.check_expression(&mut exp.tuple);
+        match self.inference.resolve(&tuple) {
+            Ty::Tuple(items) if exp.index < items.len() => items[exp.index].clone(),
+            Ty::Tuple(_) => {
+                self.error(
+                    ErrorType::InvalidField,
+                    &exp.position,
+                    exp.index.to_string(),
+                    "tuple index out of range",
+                );
+                Ty::Unknown
+            }
+            // the tuple is only known at runtime
+            Ty::Unknown | Ty::Var(_) => Ty::Unknown,
+            other => {
+                self.error(
+                    ErrorType::InvalidOperand,
+                    &exp.position,
+                    other.display(),
+                    "positional access on a value that is not a tuple",
+                );
+                Ty::Unknown
+            }
+        }
+    }
+
+    // `?` yields the wrapped value, the enclosing function must return the same wrapper
+    fn check_try(&mut self, exp: &mut TryExp) -> Ty {
+        let found = self.check_expression(&mut exp.expression);
+        let (value, wrapper) = match self.inference.resolve(&found) {
+            Ty::Option(t) => (*t, Ty::Option(Box::new(self.inference.fresh()))),
+            Ty::Result(t, e) => (*t, Ty::Result(Box::new(self.inference.fresh()), e)),
+            // only known at runtime
+            Ty::Unknown | Ty::Var(_) => return Ty::Unknown,
+            other => {
+                self.error(
+                    ErrorType::InvalidOperand,
+                    &exp.position,
+                    other.display(),
+                    "`?` expects an option or a result",
+                );
+                return Ty::Unknown;
+            }
+        };
+        let Some(expected) = self.returns.last().cloned() else {
+            self.error(
+                ErrorType::InvalidReturnLocation,
+                &exp.position,
+                "?",
+                "`?` must be inside a function",
+            );
+            return value;
+        };
+        self.expect(
+            &exp.position,
+            &expected,
+            &wrapper,
+            "`?` returns early from a function with a different return type",
+        );
+        value
+    }
+
+    fn check_field(&mut self, exp: &mut FieldExp) -> Ty {
+        let record = self.check_expression(&mut exp.record);
+        match self.inference.resolve(&record) {
+            Ty::Named(key) => {
+                let field = self
+                    .structs
+                    .get(&key)
+                    .and_then(|fields| fields.iter().find(|(f, _)| *f == exp.field))
+                    .map(|(_, ty)| ty.clone());
+                field.unwrap_or_else(|| {
+                    self.error(
+                        ErrorType::InvalidField,
+                        &exp.position,
+                        &exp.field,
+                        "record has no such field",
+                    );
+                    Ty::Unknown
+                })
+            }
+            // qualified access, e.g. lib.map
+            Ty::Module(id) => {
+                let scheme = self
+                    .modules
+                    .get(&id)
+                    .and_then(|m| m.get(&exp.field))
+                    .cloned();
+                match scheme {
+                    Some(scheme) => self.inference.instantiate(&scheme),
+                    None => {
+                        self.error(
+                            ErrorType::InvalidSymbol,
+                            &exp.position,
+                            &exp.field,
+                            "the module does not export it",
+                        );
+                        Ty::Unknown
+                    }
+                }
+            }
+            // the record is only known at runtime
+            Ty::Unknown | Ty::Var(_) => Ty::Unknown,
+            other => {
+                self.error(
+                    ErrorType::InvalidOperand,
+                    &exp.position,
+                    other.display(),
+                    "field access on a value that is not a record",
+                );
+                Ty::Unknown
+            }
+        }
+    }
+
+    fn check_function(&mut self, f: &mut Function) -> Ty {
+        // defaults are evaluated where the function is defined, they cannot see the params
+        let defaults: Vec<Option<Ty>> = f
+            .params
+            .iter_mut()
+            .map(|p| p.default.as_mut().map(|d| self.check_expression(d)))
+            .collect();
+        self.scopes.push(HashMap::new());
+        let mut params = Vec::new();
+        for (param, default) in f.params.iter().zip(defaults) {
+            self.check_annotation(&param.param_type, &param.position);
+            let ty = Ty::from_type(&param.param_type, &mut self.inference, &self.visible);
+            if let Some(found) = default {
+                let position = param
+                    .default
+                    .as_ref()
+                    .map_or(&param.position, |d| d.get_position());
+                self.expect(position, &ty, &found, "default does not match the param");
+            }
+            self.bind(&param.identifier, Scheme::mono(ty.clone()));
+            params.push(ty);
+        }
+        self.check_annotation(&f.returns, &f.position);
+        let returns = Ty::from_type(&f.returns, &mut self.inference, &self.visible);
+        self.returns.push(returns.clone());
+        // loops outside the function cannot be broken from inside it
+        let loops = std::mem::take(&mut self.loops);
+        self.check_statements(&mut f.body.statements);
+        self.loops = loops;
+        self.returns.pop();
+        self.scopes.pop();
+        // write back inferred param and return types
+        for (param, ty) in f.params.iter_mut().zip(&params) {
+            if param.param_type == Type::Infer {
+                param.param_type = self.inference.resolve(ty).to_type();
+            }
+        }
+        if f.returns == Type::Infer {
+            f.returns = self.inference.resolve(&returns).to_type();
+        }
+        Ty::Function(Some(FnTy {
+            params,
+            returns: Box::new(returns),
+            names: f.params.iter().map(|p| p.identifier.clone()).collect(),
+            required: f.required(),
+        }))
+    }
+
+    fn check_list(&mut self, l: &mut ListExp) -> Ty {
+        let element = self.inference.fresh();
+        for item in l.items.iter_mut() {
+            let found = self.check_expression(item);
+            let position = item.get_position();
+            self.expect(position, &element, &found, "list elements must match");
+        }
+        Ty::List(Box::new(element))
+    }
+
+    fn check_map(&mut self, m: &mut MapExp) -> Ty {
+        let key = self.inference.fresh();
+        let value = self.inference.fresh();
+        for (k, v) in m.entries.iter_mut() {
+            let found = self.check_expression(k);
+            self.expect(k.get_position(), &key, &found, "map keys must match");
+            let found = self.check_expression(v);
+            self.expect(v.get_position(), &value, &found, "map values must match");
+        }
+        let resolved = self.inference.resolve(&key);
+        if !resolved.is_ordered() {
+            self.error(
+                ErrorType::InvalidOperand,
+                &m.position,
+                resolved.display(),
+                "map keys must be numbers, chars, strings, or lists and tuples of them",
+            );
+        }
+        Ty::Map(Box::new(key), Box::new(value))
+    }
+
+    fn check_cons(&mut self, c: &mut ConsExp) -> Ty {
+        let head = self.check_expression(&mut c.head);
+        let tail = self.check_expression(&mut c.tail);
+        match self.inference.resolve(&tail) {
+            tail @ (Ty::List(_) | Ty::Var(_) | Ty::Unknown) => {
+                let element = self.inference.fresh();
+                self.inference
+                    .unify(&tail, &Ty::List(Box::new(element.clone())));
+                self.expect(&c.position, &element, &head, "list elements must match");
+                Ty::List(Box::new(element))
+            }
+            tail => {
+                let list = Ty::List(Box::new(Ty::Unknown));
+                self.mismatch(&c.position, &list, &tail, "cons tail must be a list");
+                Ty::List(Box::new(head))
+            }
+        }
+    }
+
+    fn check_call(&mut self, call: &mut Call) -> Ty {
+        let callee = self.check_expression(&mut call.callee);
+        let callee = self.inference.resolve(&callee);
+        let args: Vec<Ty> = call
+            .args
+            .iter_mut()
+            .map(|arg| self.check_expression(&mut arg.value))
+            .collect();
+        let placeholders: Vec<Ty> = call
+            .args
+            .iter()
+            .zip(&args)
+            .filter(|(arg, _)| matches!(arg.value, Expression::Placeholder(_)))
+            .map(|(_, ty)| ty.clone())
+            .collect();
+        let named = call.args.iter().find_map(|a| a.name.clone());
+        let sig = match callee {
+            Ty::Function(Some(f)) if named.is_some() => {
+                return self.check_named_call(call, &args, f);
+            }
+            Ty::Native(_) | Ty::Var(_) if let Some(name) = named => {
+                self.error(
+                    ErrorType::InvalidParams,
+                    &call.position,
+                    name,
+                    "only functions declared with fn take named arguments",
+                );
+                return Ty::Unknown;
+            }
+            Ty::Native(sig) => sig,
+            Ty::Function(Some(f)) => Signature {
+                required: f.required,
+                ..Signature::new(f.params, *f.returns)
+            },
+            // calling an inferred value makes it a function
+            Ty::Var(_) => {
+                let returns = self.inference.fresh();
+                let f = Ty::Function(Some(FnTy::new(args, returns.clone())));
+                self.expect(&call.position, &callee, &f, "check function call");
+                if placeholders.is_empty() {
+                    return returns;
+                }
+                return Ty::Function(Some(FnTy::new(placeholders, returns)));
+            }
+            Ty::Function(None) | Ty::Unknown => return Ty::Unknown,
+            // calling any other value evaluates to unit
+            _ => return Ty::Unit,
+        };
+        // fewer arguments than required params is a partial application, too many is an error
+        if !sig.variadic && args.len() > sig.params.len() {
+            self.error(
+                ErrorType::InvalidParams,
+                &call.position,
+                format!("found: {:?}, expected {:?}", args.len(), sig.params.len()),
+                "incorrect number of arguments",
+            );
+        }
+        for (i, (param, arg)) in sig.params.iter().zip(&args).enumerate() {
+            let position = call.args[i].value.get_position().clone();
+            self.expect(&position, param, arg, "check function call");
+        }
+        // a partial application takes the placeholders, then the missing params
+        if !placeholders.is_empty() || args.len() < sig.required {
+            let missing = sig.params.iter().take(sig.required).skip(args.len());
+            let params = placeholders
+                .iter()
+                .chain(missing)
+                .map(|t| self.inference.resolve(t))
+                .collect();
+            return Ty::Function(Some(FnTy::new(params, *sig.returns)));
+        }
+        *sig.returns
+    }
+
+    // matches the arguments to the params by position, then by name (see Interpreter::run_closure)
+    fn check_named_call(&mut self, call: &Call, args: &[Ty], f: FnTy) -> Ty {
+        if f.names.is_empty() {
+            let name = call.args.iter().find_map(|a| a.name.clone());
+            self.error(
+                ErrorType::InvalidParams,
+                &call.position,
+                name.unwrap_or_default(),
+                "only functions declared with fn take named arguments",
+            );
+            return *f.returns;
+        }
+        let mut supplied = vec![false; f.params.len()];
+        for (i, (arg, ty)) in call.args.iter().zip(args).enumerate() {
+            let position = arg.value.get_position();
+            let index = match &arg.name {
+                None if i < f.params.len() => i,
+                None => {
+                    self.error(
+                        ErrorType::InvalidParams,
+                        position,
+                        format!("found: {:?}, expected {:?}", i + 1, f.params.len()),
+                        "incorrect number of arguments",
+                    );
+                    continue;
+                }
+                Some(name) => match f.names.iter().position(|n| n == name) {
+                    Some(index) if !supplied[index] => index,
+                    found => {
+                        let message = match found {
+                            Some(_) => "argument given more than once",
+                            None => "unknown argument name",
+                        };
+                        self.error(ErrorType::InvalidParams, position, name, message);
+                        continue;
+                    }
+                },
+            };
+            supplied[index] = true;
+            self.expect(position, &f.params[index], ty, "check function call");
+        }
+        for (name, _) in f
+            .names
+            .iter()
+            .zip(supplied)
+            .take(f.required)
+            .filter(|(_, s)| !s)
+        {
+            self.error(
+                ErrorType::InvalidParams,
+                &call.position,
+                name,
+                "missing argument",
+            );
+        }
+        *f.returns
+    }
+
+    fn check_binary(&mut self, exp: &mut BinaryExp) -> Ty {
+        let left = self.check_expression(&mut exp.left);
+        let right = self.check_expression(&mut exp.right);
+        // errors report the operands as written, e.g. i32 Mod f32 rather than f64 Mod f64
+        let operands = (left.clone(), right.clone());
+        let (left, right) = self.promote(exp, left, right);
+        // numbers without a common type (e.g. bigint and f32) are not comparable
+        let numeric = {
+            let (l, r) = (
+                self.inference.resolve(&left),
+                self.inference.resolve(&right),
+            );
+            l.is_numeric() && r.is_numeric() && (l == r || !l.is_known() || !r.is_known())
+        };
+        let res = match exp.operator {
+            // modulo operator: (operands: integer types, must match; returns: same type)
+            Operator::Mod => {
+                let integer = self.inference.resolve(&left).is_integer()
+                    && self.inference.resolve(&right).is_integer();
+                if integer && self.inference.unify(&left, &right) {
+                    match self.inference.resolve(&left) {
+                        Ty::Unknown => Some(self.inference.resolve(&right)),
+                        t => Some(t),
+                    }
+                } else {
+                    None
+                }
+            }
+            // concatenation: (operands: string; returns: string)
+            Operator::Add
+                if self.inference.resolve(&left) == Ty::String
+                    || self.inference.resolve(&right) == Ty::String =>
+            {
+                (self.inference.unify(&Ty::String, &left)
+                    && self.inference.unify(&Ty::String, &right))
+                .then_some(Ty::String)
+            }
+            // arithmetic operators: (operands: numeric types, must match; returns: same type)
+            Operator::Mul | Operator::Div | Operator::Add | Operator::Sub => {
+                if numeric && self.inference.unify(&left, &right) {
+                    match self.inference.resolve(&left) {
+                        Ty::Unknown => Some(self.inference.resolve(&right)),
+                        t => Some(t),
+                    }
+                } else {
+                    None
+                }
+            }
+            // equality operators: (operands: same type, not functions; returns: bool)
+            Operator::Eq | Operator::Ne
+                if self.inference.resolve(&left).has_function()
+                    || self.inference.resolve(&right).has_function() =>
+            {
+                let left = self.inference.resolve(&left);
+                let right = self.inference.resolve(&right);
+                self.error(
+                    ErrorType::InvalidOperand,
+                    &exp.position,
+                    format!("{} {:?} {}", left.display(), exp.operator, right.display()),
+                    "functions cannot be compared",
+                );
+                Some(Ty::Bool)
+            }
+            Operator::Eq | Operator::Ne => {
+                (numeric || self.inference.unify(&left, &right)).then_some(Ty::Bool)
+            }
+            // comparison operators: (operands: numeric types, strings, lists, tuples; returns: bool)
+            Operator::Le | Operator::Ge | Operator::Lt | Operator::Gt => (numeric
+                || (self.inference.unify(&left, &right)
+                    && self.inference.resolve(&left).is_ordered()))
+            .then_some(Ty::Bool),
+            // boolean operators (operands: bool; returns: bool)
+            Operator::And | Operator::Or => (self.inference.unify(&Ty::Bool, &left)
+                && self.inference.unify(&Ty::Bool, &right))
+            .then_some(Ty::Bool),
+            // composition: (operands: functions of one param; returns: a function applying both)
+            Operator::Shl | Operator::Shr
+                if matches!(
+                    self.inference.resolve(&left),
+                    Ty::Function(_) | Ty::Native(_)
+                ) || matches!(
+                    self.inference.resolve(&right),
+                    Ty::Function(_) | Ty::Native(_)
+                ) =>
+            {
+                let (first, second) = match exp.operator {
+                    Operator::Shr => (&left, &right),
+                    _ => (&right, &left),
+                };
+                let (a, b, c) = (
+                    self.inference.fresh(),
+                    self.inference.fresh(),
+                    self.inference.fresh(),
+                );
+                let function = |param: &Ty, returns: &Ty| {
+                    Ty::Function(Some(FnTy::new(vec![param.clone()], returns.clone())))
+                };
+                if !self.inference.unify(&function(&a, &b), first)
+                    || !self.inference.unify(&function(&b, &c), second)
+                {
+                    let (first, second) = (
+                        self.inference.resolve(first),
+                        self.inference.resolve(second),
+                    );
+                    self.error(
+                        ErrorType::InvalidOperand,
+                        &exp.position,
+                        format!("{} then {}", first.display(), second.display()),
+                        "composed functions take one param, the second one takes what the first returns",
+                    );
+                }
+                Some(function(&a, &c))
+            }
+            // bitwise operators: (operands: i32; returns: i32)
+            Operator::BitAnd
+            | Operator::BitOr
+            | Operator::BitXor
+            | Operator::Shl
+            | Operator::Shr => (self.inference.unify(&Ty::I32, &left)
+                && self.inference.unify(&Ty::I32, &right))
+            .then_some(Ty::I32),
+            _ => None,
+        };
+        res.unwrap_or_else(|| {
+            let left = self.inference.resolve(&operands.0);
+            let right = self.inference.resolve(&operands.1);
+            self.error(
+                ErrorType::InvalidOperand,
+                &exp.position,
+                format!("{} {:?} {}", left.display(), exp.operator, right.display()),
+                "invalid operand types",
+            );
+            Ty::Unknown
+        })
+    }
+
+    // operands of two different numeric types are promoted to their common type
+    // (arithmetic, comparison and equality only)
+    fn promote(&mut self, exp: &BinaryExp, left: Ty, right: Ty) -> (Ty, Ty) {
+        let (l, r) = (
+            self.inference.resolve(&left),
+            self.inference.resolve(&right),
+        );
+        let promotes = matches!(
+            exp.operator,
+            Operator::Add
+                | Operator::Sub
+                | Operator::Mul
+                | Operator::Div
+                | Operator::Mod
+                | Operator::Eq
+                | Operator::Ne
+                | Operator::Lt
+                | Operator::Le
+                | Operator::Gt
+                | Operator::Ge
+        );
+        if !promotes || l == r || !l.is_known() || !r.is_known() {
+            return (left, right);
+        }
+        let Some(common) = l.promote(&r) else {
+            return (left, right);
+        };
+        if self.strict {
+            self.error(
+                ErrorType::ImplicitPromotion,
+                &exp.operator_position,
+                format!("{} {:?} {}", l.display(), exp.operator, r.display()),
+                "operands must have the same type in strict mode, convert one with to_i64, to_f64, ...",
+            );
+        }
+        (common.clone(), common)
+    }
+
+    // a number assigned to a binding of a wider numeric type is promoted to it, e.g. f64 x = 1.0
+    fn widen(&mut self, position: &Position, expected: &Ty, found: Ty) -> Ty {
+        let (e, f) = (
+            self.inference.resolve(expected),
+            self.inference.resolve(&found),
+        );
+        if e == f || !e.is_known() || !f.is_known() || f.promote(&e).as_ref() != Some(&e) {
+            return found;
+        }
+        if self.strict {
+            self.error(
+                ErrorType::ImplicitPromotion,
+                position,
+                format!("{} to {}", f.display(), e.display()),
+                "values must have the declared type in strict mode, convert with to_i64, to_f64, ...",
+            );
+        }
+        e
+    }
+
+    fn check_unary(&mut self, exp: &mut UnaryExp) -> Ty {
+        let operand = self.check_expression(&mut exp.operand);
+        let res = match exp.operator {
+            // logical not (operand: bool; returns: bool)
+            Operator::Not => self
+                .inference
+                .unify(&Ty::Bool, &operand)
+                .then_some(Ty::Bool),
+            // negation (operand: numeric; returns: same type)
+            _ => {
+                let operand = self.inference.resolve(&operand);
+                operand.is_numeric().then_some(operand)
+            }
+        };
+        res.unwrap_or_else(|| {
+            let operand = self.inference.resolve(&operand);
+            self.error(
+                ErrorType::InvalidOperand,
+                &exp.position,
+                format!("{:?} {}", exp.operator, operand.display()),
+                "invalid operand type",
+            );
+            Ty::Unknown
+        })
+    }
+
+    fn check_if(&mut self, exp: &mut IfExp) -> Ty {
+        let cond = self.check_expression(&mut exp.if_cond);
+        let position = exp.if_cond.get_position().clone();
+        self.expect(&position, &Ty::Bool, &cond, "if condition must be a bool");
+        let then_ty = self.check_block(&mut exp.then_branch.statements);
+        let else_ty = match &mut exp.else_branch {
+            Some(branch) => self.check_block(&mut branch.statements),
+            // an if without an else evaluates to unit when not taken
+            None => Ty::Unit,
+        };
+        // branches with different types are only known at runtime
+        if self.inference.unify(&then_ty, &else_ty) {
+            self.inference.resolve(&then_ty)
+        } else {
+            Ty::Unknown
+        }
+    }
+
+    // bindings inside a block are not visible after it
+    fn check_block(&mut self, block: &mut StatementList) -> Ty {
+        self.scopes.push(HashMap::new());
+        let res = self.check_statements(&mut block.statements);
+        self.scopes.pop();
+        res
+    }
+
+    /*
+     * Utility functions
+     * */
+
+    fn bind(&mut self, name: &str, scheme: Scheme) {
+        // a new binding shadows a mutable one from the same scope
+        let depth = self.scopes.len() - 1;
+        self.mutables.remove(&(depth, name.to_string()));
+        self.scopes
+            .last_mut()
+            .expect("missing scope")
+            .insert(name.to_string(), scheme);
+    }
+
+    fn lookup(&mut self, identifier: &Identifier) -> Ty {
+        let scheme = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&identifier.name))
+            .cloned();
+        match scheme {
+            Some(scheme) => self.inference.instantiate(&scheme),
+            None => {
+                self.errors.push(Error::new(
+                    ErrorType::InvalidSymbol,
+                    identifier.position.clone(),
+                    &identifier.name,
+                    None,
+                ));
+                Ty::Unknown
+            }
+        }
+    }
+
+    // reports struct names in an annotation that were never declared
+    fn check_annotation(&mut self, ty: &Type, position: &Position) {
+        match ty {
+            Type::Named(name) if !self.visible.contains_key(name) => {
+                self.error(ErrorType::InvalidSymbol, position, name, "unknown type");
+            }
+            Type::List(Some(t)) | Type::Option(t) => self.check_annotation(t, position),
+            Type::Result(t, e) | Type::Map(t, e) => {
+                self.check_annotation(t, position);
+                self.check_annotation(e, position);
+            }
+            Type::Tuple(items) => items
+                .iter()
+                .for_each(|t| self.check_annotation(t, position)),
+            Type::Function(Some(f)) => {
+                f.params
+                    .iter()
+                    .for_each(|p| self.check_annotation(p, position));
+                self.check_annotation(&f.returns, position);
+            }
+            _ => (),
+        }
+    }
+
+    // variables that are still free in the environment (cannot be generalized)
+    fn env_vars(&self) -> Vec<usize> {
+        let mut res = Vec::new();
+        for scheme in self.scopes.iter().flat_map(|scope| scope.values()) {
+            for var in self.inference.free_vars(&scheme.ty) {
+                if !scheme.vars.contains(&var) && !res.contains(&var) {
+                    res.push(var);
+                }
+            }
+        }
+        res
+    }
+
+    // unifies the types, reporting a mismatch on failure
+    fn expect(&mut self, position: &Position, expected: &Ty, found: &Ty, message: &str) -> bool {
+        if self.inference.unify(expected, found) {
+            return true;
+        }
+        let expected = self.inference.resolve(expected);
+        let found = self.inference.resolve(found);
+        self.mismatch(position, &expected, &found, message);
+        false
+    }
+
+    fn mismatch(&mut self, position: &Position, expected: &Ty, found: &Ty, message: &str) {
+        // e.g. two private structs named Node
+        let same_name = matches!((expected, found), (Ty::Named(a), Ty::Named(b)) if a.1 == b.1);
+        let (expected, found) = (expected.display(), found.display());
+        let message = if same_name {
+            format!("{message} (types with the same name declared in different files)")
+        } else {
+            message.to_string()
+        };
+        self.error(
+            ErrorType::TypeMismatch,
+            position,
+            format!("expected {expected}, found {found}"),
+            &message,
+        );
+    }
+
+    fn error(
+        &mut self,
+        error_type: ErrorType,
+        position: &Position,
+        found: impl Into<String>,
+        message: &str,
+    ) {
+        self.errors.push(Error::new(
+            error_type,
+            position.clone(),
+            found,
+            Some(message),
+        ));
+    }
+}
@@ -0,0 +1,376 @@
+use crate::{
+    parser::ast::{FunctionType, Type, TypeKey},
+    typeck::infer::Inference,
+};
+use std::collections::HashMap;
+
+/*
+* Static type
+* */
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Ty {
+    // not known until runtime (e.g. elements of a plain list)
+    Unknown,
+    // inference variable
+    Var(usize),
+    I32,
+    F32,
+    I64,
+    F64,
+    BigInt,
+    Char,
+    String,
+    Bool,
+    Unit,
+    List(Box<Ty>),
+    Tuple(Vec<Ty>),
+    Option(Box<Ty>),
+    Result(Box<Ty>, Box<Ty>),
+    Map(Box<Ty>, Box<Ty>),
+    // None for a plain `function` (any signature)
+    Function(Option<FnTy>),
+    // a struct or enum, referred to by the module declaring it and its name
+    Named(TypeKey),
+    // an imported file, referred to by its id (see loader::Module)
+    Module(String),
+    // a native function (may have optional or variadic params)
+    Native(Signature),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FnTy {
+    pub params: Vec<Ty>,
+    pub returns: Box<Ty>,
+    // param names, for named arguments (empty if the function is only known by its type)
+    pub names: Vec<String>,
+    // number of params without a default, they come first
+    pub required: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+    pub params: Vec<Ty>,
+    // number of params that must be passed
+    pub required: usize,
+    // accepts any number of extra arguments
+    pub variadic: bool,
+    pub returns: Box<Ty>,
+}
+
+// a type with quantified variables, instantiated on every use
+#[derive(Debug, Clone)]
+pub struct Scheme {
+    pub vars: Vec<usize>,
+    pub ty: Ty,
+}
+
+impl FnTy {
+    pub fn new(params: Vec<Ty>, returns: Ty) -> Self {
+        FnTy {
+            required: params.len(),
+            params,
+            returns: Box::new(returns),
+            names: Vec::new(),
+        }
+    }
+}
+
+impl Signature {
+    pub fn new(params: Vec<Ty>, returns: Ty) -> Self {
+        Signature {
+            required: params.len(),
+            params,
+            variadic: false,
+            returns: Box::new(returns),
+        }
+    }
+
+    pub fn variadic(returns: Ty) -> Self {
+        Signature {
+            params: Vec::new(),
+            required: 0,
+            variadic: true,
+            returns: Box::new(returns),
+        }
+    }
+}
+
+impl Scheme {
+    pub fn mono(ty: Ty) -> Self {
+        Scheme {
+            vars: Vec::new(),
+            ty,
+        }
+    }
+}
+
+impl Ty {
+    // converts an annotation, `_` becomes a fresh variable
+    // struct and enum names are looked up in `types` (unknown names are reported by the checker)
+    pub fn from_type(ty: &Type, inference: &mut Inference, types: &HashMap<String, TypeKey>) -> Ty {
+        match ty {
+            Type::Infer => inference.fresh(),
+            Type::I32 => Ty::I32,
+            Type::F32 => Ty::F32,
+            Type::I64 => Ty::I64,
+            Type::F64 => Ty::F64,
+            Type::BigInt => Ty::BigInt,
+            Type::Char => Ty::Char,
+            Type::String => Ty::String,
+            Type::Bool => Ty::Bool,
+            Type::Unit => Ty::Unit,
+            Type::Named(name) => types.get(name).cloned().map_or(Ty::Unknown, Ty::Named),
+            // modules cannot be written in annotations
+            Type::Module => Ty::Unknown,
+            Type::Tuple(items) => Ty::Tuple(
+                items
+                    .iter()
+                    .map(|t| Ty::from_type(t, inference, types))
+                    .collect(),
+            ),
+            Type::List(None) => Ty::List(Box::new(Ty::Unknown)),
+            Type::Option(t) => Ty::Option(Box::new(Ty::from_param(t, inference, types))),
+            Type::Result(t, e) => Ty::Result(
+                Box::new(Ty::from_param(t, inference, types)),
+                Box::new(Ty::from_param(e, inference, types)),
+            ),
+            Type::Map(k, v) => Ty::Map(
+                Box::new(Ty::from_param(k, inference, types)),
+                Box::new(Ty::from_param(v, inference, types)),
+            ),
+            Type::List(Some(t)) => Ty::List(Box::new(Ty::from_type(t, inference, types))),
+            Type::Function(None) => Ty::Function(None),
+            Type::Function(Some(f)) => Ty::Function(Some(FnTy::new(
+                f.params
+                    .iter()
+                    .map(|p| Ty::from_type(p, inference, types))
+                    .collect(),
+                Ty::from_type(&f.returns, inference, types),
+            ))),
+        }
+    }
+
+    // params left out of a plain `option` or `result` are checked at runtime
+    fn from_param(ty: &Type, inference: &mut Inference, types: &HashMap<String, TypeKey>) -> Ty {
+        match ty {
+            Type::Infer => Ty::Unknown,
+            t => Ty::from_type(t, inference, types),
+        }
+    }
+
+    // converts a resolved type back into an annotation
+    // anything still unknown is left to be checked at runtime
+    pub fn to_type(&self) -> Type {
+        match self {
+            Ty::Unknown | Ty::Var(_) => Type::Infer,
+            Ty::I32 => Type::I32,
+            Ty::F32 => Type::F32,
+            Ty::I64 => Type::I64,
+            Ty::F64 => Type::F64,
+            Ty::BigInt => Type::BigInt,
+            Ty::Char => Type::Char,
+            Ty::String => Type::String,
+            Ty::Bool => Type::Bool,
+            Ty::Unit => Type::Unit,
+            Ty::Named((_, name)) => Type::Named(name.clone()),
+            Ty::Module(_) => Type::Module,
+            Ty::Tuple(items) => Type::Tuple(items.iter().map(|t| t.to_type()).collect()),
+            Ty::List(t) => match t.to_type() {
+                Type::Infer => Type::List(None),
+                t => Type::List(Some(Box::new(t))),
+            },
+            Ty::Option(t) => Type::Option(Box::new(t.to_type())),
+            Ty::Result(t, e) => Type::Result(Box::new(t.to_type()), Box::new(e.to_type())),
+            Ty::Map(k, v) => Type::Map(Box::new(k.to_type()), Box::new(v.to_type())),
+            Ty::Function(None) | Ty::Native(_) => Type::Function(None),
+            Ty::Function(Some(f)) => Type::Function(Some(FunctionType {
+                params: f.params.iter().map(|p| p.to_type()).collect(),
+                returns: Box::new(f.returns.to_type()),
+            })),
+        }
+    }
+
+    // functions (and modules) cannot be compared, not even inside a list or tuple
+    pub fn has_function(&self) -> bool {
+        match self {
+            Ty::Function(_) | Ty::Native(_) | Ty::Module(_) => true,
+            Ty::List(t) | Ty::Option(t) => t.has_function(),
+            Ty::Result(t, e) | Ty::Map(t, e) => t.has_function() || e.has_function(),
+            Ty::Tuple(items) => items.iter().any(|t| t.has_function()),
+            _ => false,
+        }
+    }
+
+    // types compared lexicographically (or unknown until runtime)
+    pub fn is_ordered(&self) -> bool {
+        match self {
+            Ty::String | Ty::Char => true,
+            t if t.is_numeric() => true,
+            Ty::List(t) => t.is_ordered(),
+            Ty::Tuple(items) => items.iter().all(|t| t.is_ordered()),
+            _ => false,
+        }
+    }
+
+    // false until inferred, or if only known at runtime
+    pub fn is_known(&self) -> bool {
+        !matches!(self, Ty::Unknown | Ty::Var(_))
+    }
+
+    // the common type of two known numeric types (see Type::promote)
+    pub fn promote(&self, other: &Ty) -> Option<Ty> {
+        let common = Type::promote(&self.to_type(), &other.to_type())?;
+        [Ty::I32, Ty::F32, Ty::I64, Ty::F64, Ty::BigInt]
+            .into_iter()
+            .find(|t| t.to_type() == common)
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer() || matches!(self, Ty::F32 | Ty::F64)
+    }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            Ty::Unknown | Ty::Var(_) | Ty::I32 | Ty::I64 | Ty::BigInt
+        )
+    }
+
+    pub fn display(&self) -> String {
+        match self {
+            Ty::Unknown => "unknown".to_string(),
+            // not inferred yet, written like the annotation that asks for inference
+            Ty::Var(_) => "_".to_string(),
+            Ty::Native(_) => Type::Function(None).display(),
+            Ty::List(t) if **t == Ty::Unknown => Type::List(None).display(),
+            Ty::List(t) => format!("list<{}>", t.display()),
+            Ty::Option(t) => format!("option<{}>", t.display()),
+            Ty::Result(t, e) => format!("result<{}, {}>", t.display(), e.display()),
+            Ty::Map(k, v) if **k == Ty::Unknown && **v == Ty::Unknown => "map".to_string(),
+            Ty::Map(k, v) => format!("map<{}, {}>", k.display(), v.display()),
+            Ty::Tuple(items) => {
+                let items: Vec<String> = items.iter().map(|t| t.display()).collect();
+                format!("({})", items.join(", "))
+            }
+            Ty::Function(Some(f)) => {
+                let params: Vec<String> = f.params.iter().map(|p| p.display()).collect();
+                format!("fn({}) -> {}", params.join(", "), f.returns.display())
+            }
+            t => t.to_type().display(),
+        }
+    }
+}
+
+// signatures of the functions in the stdlib scope
+pub fn get_stdlib_types(inference: &mut Inference) -> HashMap<String, Scheme> {
+    let list_of = |t: &Ty| Ty::List(Box::new(t.clone()));
+    let mut assert = Signature::new(vec![Ty::Bool, Ty::String], Ty::Unit);
+    assert.required = 1;
+    let option_of = |t: &Ty| Ty::Option(Box::new(t.clone()));
+    let result_of = |t: &Ty, e: &Ty| Ty::Result(Box::new(t.clone()), Box::new(e.clone()));
+    let map_of = |k: &Ty, v: &Ty| Ty::Map(Box::new(k.clone()), Box::new(v.clone()));
+    // list, option and result functions are generic over their element types
+    let a = inference.fresh();
+    let e = inference.fresh();
+    // map functions over their key and value types
+    let (k, v) = (inference.fresh(), inference.fresh());
+    let map = map_of(&k, &v);
+    let generic = vec![
+        ("head", Signature::new(vec![list_of(&a)], a.clone())),
+        ("tail", Signature::new(vec![list_of(&a)], list_of(&a))),
+        ("length", Signature::new(vec![list_of(&a)], Ty::I32)),
+        ("try_head", Signature::new(vec![list_of(&a)], option_of(&a))),
+        (
+            "try_tail",
+            Signature::new(vec![list_of(&a)], option_of(&list_of(&a))),
+        ),
+        ("some", Signature::new(vec![a.clone()], option_of(&a))),
+        ("ok", Signature::new(vec![a.clone()], result_of(&a, &e))),
+        ("err", Signature::new(vec![e.clone()], result_of(&a, &e))),
+        ("to_string", Signature::new(vec![a.clone()], Ty::String)),
+        (
+            "get",
+            Signature::new(vec![map.clone(), k.clone()], option_of(&v)),
+        ),
+        (
+            "insert",
+            Signature::new(vec![map.clone(), k.clone(), v.clone()], map.clone()),
+        ),
+        (
+            "remove",
+            Signature::new(vec![map.clone(), k.clone()], map.clone()),
+        ),
+        ("keys", Signature::new(vec![map.clone()], list_of(&k))),
+        ("values", Signature::new(vec![map.clone()], list_of(&v))),
+        (
+            "contains_key",
+            Signature::new(vec![map.clone(), k.clone()], Ty::Bool),
+        ),
+    ];
+    let simple = vec![
+        ("floor", Signature::new(vec![Ty::F32], Ty::I32)),
+        ("print", Signature::variadic(Ty::Unit)),
+        ("println", Signature::variadic(Ty::Unit)),
+        ("panic", Signature::variadic(Ty::Unit)),
+        ("read", Signature::new(vec![], Ty::String)),
+        (
+            "try_read",
+            Signature::new(vec![], result_of(&Ty::String, &Ty::String)),
+        ),
+        ("assert", assert),
+        // conversions accept any number (and to_bigint a string), checked at runtime
+        ("to_i32", Signature::new(vec![Ty::Unknown], Ty::I32)),
+        ("to_i64", Signature::new(vec![Ty::Unknown], Ty::I64)),
+        ("to_f32", Signature::new(vec![Ty::Unknown], Ty::F32)),
+        ("to_f64", Signature::new(vec![Ty::Unknown], Ty::F64)),
+        ("to_bigint", Signature::new(vec![Ty::Unknown], Ty::BigInt)),
+        (
+            "chars",
+            Signature::new(vec![Ty::String], list_of(&Ty::Char)),
+        ),
+        (
+            "from_chars",
+            Signature::new(vec![list_of(&Ty::Char)], Ty::String),
+        ),
+        (
+            "code_points",
+            Signature::new(vec![Ty::String], list_of(&Ty::I32)),
+        ),
+        (
+            "from_code_points",
+            Signature::new(vec![list_of(&Ty::I32)], Ty::String),
+        ),
+        (
+            "graphemes",
+            Signature::new(vec![Ty::String], list_of(&Ty::String)),
+        ),
+        (
+            "grapheme_at",
+            Signature::new(vec![Ty::String, Ty::I32], Ty::String),
+        ),
+        (
+            "try_grapheme_at",
+            Signature::new(vec![Ty::String, Ty::I32], option_of(&Ty::String)),
+        ),
+    ];
+    let mut res = HashMap::new();
+    for (name, sig) in generic {
+        let ty = Ty::Native(sig);
+        let scheme = Scheme {
+            vars: inference.free_vars(&ty),
+            ty,
+        };
+        res.insert(name.to_string(), scheme);
+    }
+    let none = option_of(&a);
+    let scheme = Scheme {
+        vars: inference.free_vars(&none),
+        ty: none,
+    };
+    res.insert("none".to_string(), scheme);
+    for (name, sig) in simple {
+        res.insert(name.to_string(), Scheme::mono(Ty::Native(sig)));
+    }
+    res
+}
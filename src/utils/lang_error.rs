@@ -14,6 +14,8 @@ pub enum ErrorType {
     // Lexer
     InvalidChar,
     UnterminatedStringLiteral,
+    InvalidEscape,
+    InvalidCharLiteral,
     InvalidIntLiteral,
     InvalidFloatLiteral,
     InvalidOperator,
@@ -25,12 +27,23 @@ pub enum ErrorType {
     InvalidSymbol,
     UnexpectedStatementType,
     InvalidReturnLocation,
+    InvalidBreakLocation,
     UnexpectedExecResult,
     InvalidOperand,
     InvalidParams,
     InvalidFunctionBody,
     TypeMismatch,
     EmptyList,
+    IndexOutOfBounds,
+    InvalidField,
+    NonExhaustiveMatch,
+    ImmutableAssignment,
+    DivisionByZero,
+    IntegerOverflow,
+    ImplicitPromotion,
+    // Loader
+    InvalidImport,
+    ImportCycle,
     // Stdlib
     StdRead,
     StdMissingArgs,
@@ -45,6 +58,8 @@ pub struct Error {
     pub position: Position,
     pub found: String,
     pub message: Option<String>,
+    // the imported file the error is in (None for the file that was run)
+    pub file: Option<String>,
 }
 
 const NO_POSITION: Position = Position { col: 0, line: 0 };
@@ -61,21 +76,33 @@ impl Error {
             position,
             found: found.into(),
             message: message.map(|m| m.into()),
+            file: None,
         }
     }
 
+    // attributes the error to a file, unless it already is
+    pub fn in_file(mut self, file: &str) -> Self {
+        self.file.get_or_insert_with(|| file.to_string());
+        self
+    }
+
     pub fn display(&self) -> String {
+        let file = match &self.file {
+            Some(f) => format!("\nfile: {}", f),
+            None => String::new(),
+        };
         format!(
-            "---\nerror: {:?} at line {}, col {}\nfound: '{}'\ninfo: {}\n---",
+            "---\nerror: {:?} at line {}, col {}{}\nfound: '{}'\ninfo: {}\n---",
             self.error_type,
             self.position.line,
             self.position.col,
+            file,
             self.found,
             self.message.as_deref().unwrap_or("none"),
         )
     }
 
-    /**
+    /*
      * Functions for creating common errors
      * */
 
@@ -104,14 +131,15 @@ impl Error {
         Error {
             error_type: ErrorType::UnexpectedExecResult,
             position: NO_POSITION,
-            found: found,
+            found,
             message: msg,
+            file: None,
         }
     }
 
     pub fn generic_invalid_operand(operand: &Value, expected: Option<&str>) -> Self {
         let op = format!("{:?}", operand.get_type());
-        return Error::new(ErrorType::InvalidOperand, NO_POSITION, op, expected);
+        Error::new(ErrorType::InvalidOperand, NO_POSITION, op, expected)
     }
 
     pub fn generic_eof(expected: &str) -> Self {
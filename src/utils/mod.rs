@@ -1,2 +1,3 @@
 pub mod lang_error;
+pub mod options;
 pub mod position;
@@ -0,0 +1,36 @@
+/*
+* Options
+* */
+
+// settings chosen on the command line
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub overflow: Overflow,
+    // implicit numeric promotion (e.g. `1 + 2.0`) is an error
+    pub strict: bool,
+    // directories searched for imports not found next to the importing file
+    pub search_path: Vec<String>,
+}
+
+// how integer arithmetic that overflows is handled
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Overflow {
+    // overflow is an error
+    #[default]
+    Checked,
+    // wraps around, e.g. i32 max + 1 is i32 min
+    Wrapping,
+    // clamps to the bounds of the type, e.g. i32 max + 1 is i32 max
+    Saturating,
+}
+
+impl Overflow {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "checked" => Some(Overflow::Checked),
+            "wrapping" => Some(Overflow::Wrapping),
+            "saturating" => Some(Overflow::Saturating),
+            _ => None,
+        }
+    }
+}
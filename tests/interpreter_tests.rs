@@ -0,0 +1,184 @@
+use lang::{
+    interpreter::{interpret_modules, interpret_with},
+    lexer::tokenize,
+    loader::load,
+    parser::parse,
+    typeck::{check_modules, check_with},
+    utils::{
+        lang_error::{Error, ErrorType},
+        options::{Options, Overflow},
+    },
+};
+
+#[test]
+fn overflow_is_checked_by_default() {
+    let err = run("let n = 2147483647 + 1", Overflow::Checked).unwrap_err();
+    assert!(matches!(err.error_type, ErrorType::IntegerOverflow));
+    // points at the operator
+    assert_eq!((err.position.line, err.position.col), (1, 20));
+}
+
+#[test]
+fn overflow_wraps() {
+    let program = r#"
+    assert(2147483647 + 1 == 0 - 2147483647 - 1)
+    assert(-(0 - 2147483647 - 1) == 0 - 2147483647 - 1)
+    assert(9223372036854775807i64 * 2i64 == -2i64)
+    "#;
+    run(program, Overflow::Wrapping).unwrap();
+}
+
+#[test]
+fn overflow_saturates() {
+    let program = r#"
+    assert(2147483647 + 1 == 2147483647)
+    assert(0 - 2147483647 - 10 == 0 - 2147483647 - 1)
+    assert(-(0 - 2147483647 - 1) == 2147483647)
+    assert((0 - 2147483647 - 1) / -1 == 2147483647)
+    "#;
+    run(program, Overflow::Saturating).unwrap();
+}
+
+#[test]
+fn division_by_zero_is_an_error_in_every_mode() {
+    for mode in [Overflow::Checked, Overflow::Wrapping, Overflow::Saturating] {
+        let err = run("let n = 15 % 0", mode).unwrap_err();
+        assert!(matches!(err.error_type, ErrorType::DivisionByZero));
+    }
+}
+
+#[test]
+fn mixed_operands_are_promoted() {
+    let program = r#"
+    assert(1 + 2i64 == 3i64)
+    assert(to_bigint(9223372036854775807i64) + 1 == to_bigint("9223372036854775808"))
+    assert(1 / 2.0 == 0.5)
+    assert(2i64 * 1.5 == 3.0f64)
+    assert(1i64 < 2.5)
+    assert(3 == 3.0)
+    // annotated bindings widen their value
+    f64 h = 1.0
+    assert(h / 3 == 1.0f64 / 3.0f64)
+    mut i64 n = 1
+    n = 2
+    // an i32 would overflow here
+    assert(n * 2147483647 == 4294967294i64)
+    "#;
+    run(program, Overflow::Checked).unwrap();
+}
+
+#[test]
+fn strict_mode_rejects_promotion() {
+    let options = Options {
+        strict: true,
+        ..Options::default()
+    };
+    let err = run_with("let n = 1 + 2i64", options.clone()).unwrap_err();
+    assert!(matches!(err.error_type, ErrorType::ImplicitPromotion));
+    let err = run_with("f64 h = 1.0", options.clone()).unwrap_err();
+    assert!(matches!(err.error_type, ErrorType::ImplicitPromotion));
+    run_with("let n = 1i64 + 2i64", options).unwrap();
+}
+
+#[test]
+fn imports_use_the_search_path() {
+    let dir = std::env::temp_dir().join(format!("lang-search-path-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let main = dir.join("main.lang");
+    let program = r#"
+    import { range } from "lists.lang"
+    assert(range(0, 3) == [0, 1, 2])
+    "#;
+    std::fs::write(&main, program).unwrap();
+    let main = main.to_str().unwrap();
+    // not next to the importing file
+    let err = load(main, &Options::default()).unwrap_err();
+    assert!(matches!(err.error_type, ErrorType::InvalidImport));
+    let options = Options {
+        search_path: vec!["./tests/cases/modules".to_string()],
+        ..Options::default()
+    };
+    let mut modules = load(main, &options).unwrap();
+    assert_eq!(modules.len(), 3);
+    check_modules(&mut modules, &options).unwrap();
+    interpret_modules(modules, options).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn runtime_errors_name_the_imported_file() {
+    let dir = std::env::temp_dir().join(format!("lang-error-file-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let lib = r#"
+    export let ratio = fn(a: i32, b: i32) -> i32 {
+      a / b
+    }
+    export let apply = fn(f: fn(i32) -> i32) -> i32 { f(0) }
+    "#;
+    std::fs::write(dir.join("lib.lang"), lib).unwrap();
+    let run_main = |name: &str, program: &str| {
+        let main = dir.join(name);
+        std::fs::write(&main, program).unwrap();
+        let options = Options::default();
+        let mut modules = load(main.to_str().unwrap(), &options).unwrap();
+        check_modules(&mut modules, &options).unwrap();
+        interpret_modules(modules, options).unwrap_err()
+    };
+    // in a function of the imported file
+    let err = run_main(
+        "call.lang",
+        "import { ratio } from \"lib.lang\"\nratio(1, 0)",
+    );
+    assert!(matches!(err.error_type, ErrorType::DivisionByZero));
+    assert_eq!(err.position.line, 3);
+    assert!(err.file.as_deref().is_some_and(|f| f.ends_with("lib.lang")));
+    // at the top level of the imported file
+    std::fs::write(dir.join("top.lang"), "let x = 1\nlet y = x / 0").unwrap();
+    let err = run_main("top_main.lang", "import \"top.lang\" as top");
+    assert_eq!(err.position.line, 2);
+    assert!(err.file.as_deref().is_some_and(|f| f.ends_with("top.lang")));
+    // in a function of the entry file, called from the imported file
+    let program = "import { apply } from \"lib.lang\"\n\napply(fn(n: i32) -> i32 { 1 / n })";
+    let err = run_main("callback.lang", program);
+    assert_eq!((err.position.line, err.file), (3, None));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn named_arguments_are_checked_at_runtime() {
+    // without the type checker
+    let run_unchecked = |program: &str| {
+        let ast = parse(tokenize(program.to_string())?)?;
+        interpret_with(ast, Options::default())
+    };
+    let range = "let range = fn(from: i32, to: i32, step: i32 = 1) -> i32 { (to - from) / step }";
+    run_unchecked(&format!("{range}; assert(range(0, 10, step: 2) == 5)")).unwrap();
+    for (call, message) in [
+        ("range(0, 10, stride: 2)", "unknown argument name"),
+        ("range(0, 10, to: 2)", "argument given more than once"),
+        ("range(0, step: 2)", "missing argument"),
+        (
+            "length([1], l: 2)",
+            "only functions declared with fn take named arguments",
+        ),
+    ] {
+        let err = run_unchecked(&format!("{range}; {call}")).unwrap_err();
+        assert!(matches!(err.error_type, ErrorType::InvalidParams));
+        assert_eq!(err.message.as_deref(), Some(message), "{call}");
+    }
+}
+
+fn run(program: &str, overflow: Overflow) -> Result<(), Error> {
+    let options = Options {
+        overflow,
+        ..Options::default()
+    };
+    run_with(program, options)
+}
+
+fn run_with(program: &str, options: Options) -> Result<(), Error> {
+    let tokens = tokenize(program.to_string())?;
+    let mut ast = parse(tokens)?;
+    check_with(&mut ast, &options).map_err(|mut errors| errors.remove(0))?;
+    interpret_with(ast, options)
+}
@@ -1,5 +1,6 @@
 use lang::lexer::token::*;
 use lang::lexer::tokenize;
+use lang::utils::lang_error::ErrorType;
 
 #[test]
 fn test_tokenizer() {
@@ -47,7 +48,7 @@ fn test_tokenizer() {
 
 #[test]
 fn test_operators_separators() {
-    let program = "[]%::{}(())+ -/ * ==!= <;; > <=>= && || ! =: - ->".to_string();
+    let program = "[]%::{}(())+ -/ * ==!= <;; > <=>= && || ! =: - -> .".to_string();
     let expected = vec![
         TokenKind::Separator(Separator::LBracket),
         TokenKind::Separator(Separator::RBracket),
@@ -78,10 +79,183 @@ fn test_operators_separators() {
         TokenKind::Separator(Separator::Colon),
         TokenKind::Operator(Operator::Sub),
         TokenKind::Separator(Separator::Arrow),
+        TokenKind::Separator(Separator::Dot),
     ];
     compare_output(program, expected, false);
 }
 
+#[test]
+fn test_bitwise_operators() {
+    let program = "& | ^ << >> &&| |> ||>".to_string();
+    let expected = vec![
+        TokenKind::Operator(Operator::BitAnd),
+        TokenKind::Operator(Operator::BitOr),
+        TokenKind::Operator(Operator::BitXor),
+        TokenKind::Operator(Operator::Shl),
+        TokenKind::Operator(Operator::Shr),
+        TokenKind::Operator(Operator::And),
+        TokenKind::Operator(Operator::BitOr),
+        TokenKind::Operator(Operator::Pipe),
+        TokenKind::Operator(Operator::Or),
+        TokenKind::Operator(Operator::Gt),
+    ];
+    compare_output(program, expected, false);
+}
+
+#[test]
+fn test_tuple_index() {
+    let program = "t.0.1 1.5".to_string();
+    let expected = vec![
+        TokenKind::Identifier("t".to_string()),
+        TokenKind::Separator(Separator::Dot),
+        TokenKind::Literal(Literal::Int(0)),
+        TokenKind::Separator(Separator::Dot),
+        TokenKind::Literal(Literal::Int(1)),
+        TokenKind::Literal(Literal::Float(1.5)),
+    ];
+    compare_output(program, expected, false);
+}
+
+#[test]
+fn test_number_literals() {
+    let program = "0xFF 0b1010 0o17 1_000 1.5e-3 2E2 10i32 2f32 0x1Fi32".to_string();
+    let expected = vec![
+        TokenKind::Literal(Literal::Int(255)),
+        TokenKind::Literal(Literal::Int(10)),
+        TokenKind::Literal(Literal::Int(15)),
+        TokenKind::Literal(Literal::Int(1000)),
+        TokenKind::Literal(Literal::Float(1.5e-3)),
+        TokenKind::Literal(Literal::Float(200.0)),
+        TokenKind::Literal(Literal::Int(10)),
+        TokenKind::Literal(Literal::Float(2.0)),
+        TokenKind::Literal(Literal::Int(31)),
+    ];
+    compare_output(program, expected, false);
+    let program = "3_000_000_000i64 0xFFi64 2.5f64 1e300f64".to_string();
+    let expected = vec![
+        TokenKind::Literal(Literal::Int64(3_000_000_000)),
+        TokenKind::Literal(Literal::Int64(255)),
+        TokenKind::Literal(Literal::Float64(2.5)),
+        TokenKind::Literal(Literal::Float64(1e300)),
+    ];
+    compare_output(program, expected, false);
+    let errors = [
+        ("1.2.3", "one decimal point"),
+        ("0x", "expected hex digits"),
+        ("0o8", "invalid digit '8'"),
+        ("1_", "separators"),
+        ("1e", "exponent"),
+        ("1.5i32", "integer suffix"),
+        ("7abc", "unknown suffix"),
+        ("2147483648", "i64 suffix"),
+        ("1.5i64", "integer suffix"),
+        ("9223372036854775808i64", "does not fit in i64"),
+    ];
+    for (program, message) in errors {
+        let err = tokenize(program.to_string()).unwrap_err();
+        assert_eq!(err.found, program);
+        let info = err.message.unwrap_or_default();
+        assert!(info.contains(message), "{}: {}", program, info);
+    }
+}
+
+#[test]
+fn test_try() {
+    let program = "option<i32> x = f()?".to_string();
+    let expected = vec![
+        TokenKind::Keyword(Keyword::Option),
+        TokenKind::Operator(Operator::Lt),
+        TokenKind::Keyword(Keyword::I32),
+        TokenKind::Operator(Operator::Gt),
+        TokenKind::Identifier("x".to_string()),
+        TokenKind::Operator(Operator::Assign),
+        TokenKind::Identifier("f".to_string()),
+        TokenKind::Separator(Separator::LParen),
+        TokenKind::Separator(Separator::RParen),
+        TokenKind::Operator(Operator::Try),
+    ];
+    compare_output(program, expected, false);
+}
+
+#[test]
+fn test_interpolation() {
+    let program = r#""total: ${sum(xs)}!" "${"}"}" "$x""#.to_string();
+    let code = tokenize("sum(xs)".to_string()).unwrap();
+    let inner = tokenize(r#""}""#.to_string()).unwrap();
+    let tokens = tokenize(program).unwrap();
+    assert_eq!(tokens.len(), 3);
+    let TokenKind::Interpolation(parts) = &tokens[0].kind else {
+        panic!("expected an interpolation, found {:?}", tokens[0].kind);
+    };
+    assert_eq!(parts.len(), 3);
+    assert_eq!(parts[0], StringPart::Text("total: ".to_string()));
+    let StringPart::Code(embedded, start) = &parts[1] else {
+        panic!("expected embedded code");
+    };
+    let kinds: Vec<&TokenKind> = embedded.iter().map(|t| &t.kind).collect();
+    assert_eq!(kinds, code.iter().map(|t| &t.kind).collect::<Vec<_>>());
+    // embedded tokens keep their position in the source
+    assert_eq!(embedded[0].position.col, 11);
+    assert_eq!(start.col, 9);
+    assert_eq!(parts[2], StringPart::Text("!".to_string()));
+    let TokenKind::Interpolation(parts) = &tokens[1].kind else {
+        panic!("expected an interpolation");
+    };
+    assert!(matches!(&parts[0], StringPart::Code(c, _) if c[0].kind == inner[0].kind));
+    assert_eq!(
+        tokens[2].kind,
+        TokenKind::Literal(Literal::String("$x".to_string()))
+    );
+}
+
+#[test]
+fn test_string_literals() {
+    let program = r#""" "a\tb\"\u{48}" r"\n${x}" """
+        first
+          second
+        """"#
+        .to_string();
+    let expected = vec![
+        TokenKind::Literal(Literal::String("".to_string())),
+        TokenKind::Literal(Literal::String("a\tb\"H".to_string())),
+        TokenKind::Literal(Literal::String("\\n${x}".to_string())),
+        TokenKind::Literal(Literal::String("first\n  second".to_string())),
+    ];
+    compare_output(program, expected, false);
+}
+
+#[test]
+fn test_invalid_escape() {
+    let err = tokenize(r#"  "ok \x""#.to_string()).unwrap_err();
+    assert!(matches!(err.error_type, ErrorType::InvalidEscape));
+    // points at the backslash
+    assert_eq!((err.position.line, err.position.col), (1, 7));
+    print_err(&err.display(), false);
+    assert!(tokenize(r#""\u{zz}""#.to_string()).is_err());
+}
+
+#[test]
+fn test_char_literals() {
+    let program = r"char c = 'a' '\n' '\'' '\u{1F600}' '€'".to_string();
+    let expected = vec![
+        TokenKind::Keyword(Keyword::Char),
+        TokenKind::Identifier("c".to_string()),
+        TokenKind::Operator(Operator::Assign),
+        TokenKind::Literal(Literal::Char('a')),
+        TokenKind::Literal(Literal::Char('\n')),
+        TokenKind::Literal(Literal::Char('\'')),
+        TokenKind::Literal(Literal::Char('😀')),
+        TokenKind::Literal(Literal::Char('€')),
+    ];
+    compare_output(program, expected, false);
+    for program in ["''", "'ab'", "'a", r"'\q'"] {
+        assert!(tokenize(program.to_string()).is_err(), "{}", program);
+    }
+    let err = tokenize("'ab'".to_string()).unwrap_err();
+    assert!(matches!(err.error_type, ErrorType::InvalidCharLiteral));
+    assert_eq!(err.found, "'ab'");
+}
+
 #[test]
 fn test_error() {
     let program = "\"i am not going to terminate this string literal".to_string();
@@ -95,20 +269,44 @@ fn test_error() {
     let tokens = tokenize(program);
     assert!(tokens.is_err());
     print_err(&tokens.unwrap_err().display(), false);
+    let program = "\"unterminated ${interpolation\"".to_string();
+    let err = tokenize(program).unwrap_err();
+    // points at the `${`
+    assert_eq!((err.position.line, err.position.col), (1, 15));
+    print_err(&err.display(), false);
 }
 
 #[test]
 fn test_keywords() {
-    let program = "fn i32 bool string true false if else".to_string();
+    let program =
+        "fn i32 f32 i64 f64 bigint bool string true false if else let mut struct match enum while for in break continue _ _x =>"
+        .to_string();
     let expected = vec![
         TokenKind::Keyword(Keyword::Fn),
         TokenKind::Keyword(Keyword::I32),
+        TokenKind::Keyword(Keyword::F32),
+        TokenKind::Keyword(Keyword::I64),
+        TokenKind::Keyword(Keyword::F64),
+        TokenKind::Keyword(Keyword::BigInt),
         TokenKind::Keyword(Keyword::Bool),
         TokenKind::Keyword(Keyword::String),
         TokenKind::Keyword(Keyword::True),
         TokenKind::Keyword(Keyword::False),
         TokenKind::Keyword(Keyword::If),
         TokenKind::Keyword(Keyword::Else),
+        TokenKind::Keyword(Keyword::Let),
+        TokenKind::Keyword(Keyword::Mut),
+        TokenKind::Keyword(Keyword::Struct),
+        TokenKind::Keyword(Keyword::Match),
+        TokenKind::Keyword(Keyword::Enum),
+        TokenKind::Keyword(Keyword::While),
+        TokenKind::Keyword(Keyword::For),
+        TokenKind::Keyword(Keyword::In),
+        TokenKind::Keyword(Keyword::Break),
+        TokenKind::Keyword(Keyword::Continue),
+        TokenKind::Identifier("_".to_string()),
+        TokenKind::Identifier("_x".to_string()),
+        TokenKind::Separator(Separator::FatArrow),
     ];
     compare_output(program, expected, false);
 }
@@ -1,7 +1,10 @@
 use lang::{
     lexer::tokenize,
-    parser::{ast::StatementList, parse},
-    utils::lang_error::Error,
+    parser::{
+        ast::{Expression, Import, ImportNames, Statement, StatementList},
+        parse,
+    },
+    utils::lang_error::{Error, ErrorType},
 };
 
 #[test]
@@ -9,6 +12,254 @@ fn parse_simple() {
     check(parse_str("i32 digit = 2;"));
 }
 
+#[test]
+fn parse_types() {
+    check(parse_str("list<list<string>> words = [];"));
+    check(parse_str("fn(i32, list<i32>) -> fn() -> i32 f = g;"));
+    // an anonymous function is not a declaration
+    check(parse_str("fn() -> i32 { 1 }();"));
+    // return types can be left to inference
+    check(parse_str("let id = fn(x) -> _ { x };"));
+    check(parse_str("let id = fn(x) { x };"));
+}
+
+#[test]
+fn parse_records() {
+    check(parse_str("struct Point { x: i32, y: list<i32>, }"));
+    check(parse_str("Point p = Point { x: 1, y: [] }; p.y;"));
+    check(parse_str("fn(p: Point) -> i32 { p.x }(Point {}).x;"));
+}
+
+#[test]
+fn parse_enums() {
+    check(parse_str(
+        "enum Shape { Circle(f32), Rect(f32, f32), Empty, }",
+    ));
+    check(parse_str(
+        "match (s) { Circle(r) => r, Rect(_, h) => { h } Empty => 0.0 }",
+    ));
+    check(parse_str(
+        "match (n) { -1 => true, 0 => false, () => true, x => x }",
+    ));
+}
+
+#[test]
+fn parse_list_patterns() {
+    check(parse_str(
+        "match l { [] => 0, [a, b] => a, h :: t if h > 0 => h, _ => 0 }",
+    ));
+    check(parse_str(
+        "match l { (a :: b) :: c :: rest => a, Some([x]) => x }",
+    ));
+}
+
+#[test]
+fn parse_tuples() {
+    check(parse_str("(i32, string) t = (1, \"one\"); t.0; t.1.0;"));
+    check(parse_str("(i32 q, r) = divmod(7, 2)"));
+    check(parse_str("let (a, b) = (1, 2)"));
+    check(parse_str("match p { (a, (b, _)) => a, () => 0 }"));
+    // a paren on a new line is not a call
+    let ast = parse_str("let f = g\n(a, b) = (1, 2)").unwrap();
+    assert_eq!(ast.statements.len(), 2);
+}
+
+#[test]
+fn parse_options() {
+    check(parse_str(
+        "option<i32> a = none; result<i32, string> b = ok(1); option c = some(2)",
+    ));
+    check(parse_str(
+        "fn(l: list<i32>) -> option<i32> { try_head(l)? }",
+    ));
+    check(parse_str(
+        "match r { ok(some(x)) => x, ok(none) => 0, err(_) => 1 }",
+    ));
+}
+
+#[test]
+fn parse_reassignment() {
+    let ast = parse_str("let mut x = 1; mut i32 y = 2; x = x + y").unwrap();
+    let a = ast.statements[0].expect_assignment().unwrap();
+    assert!(a.mutable);
+    assert!(ast.statements[1].expect_assignment().unwrap().mutable);
+    assert!(matches!(ast.statements[2], Statement::Reassignment(_)));
+    // a reassignment cannot end a function
+    assert!(parse_str("fn() -> unit { x = 1 }").is_err());
+}
+
+#[test]
+fn parse_loops() {
+    let ast = parse_str("while (i < 3) { i = i + 1; continue } for x in xs { break }").unwrap();
+    assert!(matches!(ast.statements[0], Statement::While(_)));
+    assert!(matches!(ast.statements[1], Statement::For(_)));
+    // loop bodies need braces
+    assert!(parse_str("while (true) break").is_err());
+    // `xs {}` is the iterable and the body, not a record literal
+    let ast = parse_str("for x in xs {}\nprintln(1)").unwrap();
+    assert!(matches!(&ast.statements[0], Statement::For(f)
+        if matches!(f.iterable, Expression::IdentifierExp(_)) && f.body.statements.is_empty()));
+    assert!(matches!(ast.statements[1], Statement::Expression(_)));
+    check(parse_str("for x in xs { println(x) }"));
+    // records are allowed in parens and arguments
+    check(parse_str(
+        "for x in (Bag { items: xs }).items { println(x) }",
+    ));
+    check(parse_str("for x in items(Bag { items: xs }) {}"));
+}
+
+#[test]
+fn parse_match_subjects() {
+    let ast = parse_str("match v {}\nmatch v { x => x }").unwrap();
+    for stmt in &ast.statements {
+        let exp = stmt.expect_expression().unwrap();
+        assert!(matches!(exp, Expression::MatchExp(m)
+            if matches!(*m.subject, Expression::IdentifierExp(_))));
+    }
+    check(parse_str("match (Pair { a: 1 }).a { _ => 1 }"));
+}
+
+#[test]
+fn parse_blocks() {
+    check(parse_str("if (c) { i32 y = 2; println(y) } else { 1 }"));
+    check(parse_str("let x = { let a = 1; a + 1 }"));
+    check(parse_str("match l { [] => { 0 }, _ => { let n = 1; n } }"));
+    assert!(parse_str("let x = { 1").is_err());
+}
+
+#[test]
+fn parse_operators() {
+    check(parse_str("let a = !done && -x < 1 << 2 | 3 & 4 ^ 5 >> 1"));
+    check(parse_str(
+        "list<list<i32>> grid = [[1]]; option<list<i32>> o = none",
+    ));
+    // unary binds tighter than binary operators
+    let ast = parse_str("-a * b").unwrap();
+    let exp = ast.statements[0].expect_expression().unwrap();
+    assert!(matches!(exp, Expression::BinaryExp(_)));
+}
+
+#[test]
+fn parse_pipes() {
+    // lowered into calls, the value is the first argument
+    let ast = parse_str("xs |> map(double) |> length").unwrap();
+    let exp = ast.statements[0].expect_expression().unwrap();
+    let Expression::CallExp(outer) = exp else {
+        panic!("expected a call, found {:?}", exp);
+    };
+    assert_eq!(outer.args.len(), 1);
+    assert!(matches!(&outer.args[0].value, Expression::CallExp(inner) if inner.args.len() == 2));
+    // binds looser than arithmetic, tighter than comparison
+    let ast = parse_str("1 + 2 |> f == 3").unwrap();
+    let exp = ast.statements[0].expect_expression().unwrap();
+    assert!(matches!(exp, Expression::BinaryExp(b) if matches!(*b.left, Expression::CallExp(_))));
+    assert!(parse_str("xs |>").is_err());
+}
+
+#[test]
+fn parse_placeholders() {
+    let ast = parse_str("sub(_, 1); f(_x, __)").unwrap();
+    let exp = ast.statements[0].expect_expression().unwrap();
+    assert!(matches!(exp, Expression::CallExp(c)
+        if matches!(c.args[0].value, Expression::Placeholder(_))));
+    // only a lone `_` is a placeholder
+    let exp = ast.statements[1].expect_expression().unwrap();
+    assert!(matches!(exp, Expression::CallExp(c)
+        if c.args.iter().all(|a| matches!(a.value, Expression::IdentifierExp(_)))));
+    // a piped value fills the first placeholder
+    let ast = parse_str("x |> sub(10, _)").unwrap();
+    let exp = ast.statements[0].expect_expression().unwrap();
+    assert!(matches!(exp, Expression::CallExp(c)
+        if c.args.len() == 2 && matches!(c.args[1].value, Expression::IdentifierExp(_))));
+}
+
+#[test]
+fn parse_named_arguments() {
+    let ast = parse_str("let r = fn(a: i32, step: i32 = 1) -> i32 { a }; r(0, step: 2)").unwrap();
+    let r = ast.statements[0].expect_assignment().unwrap();
+    let Expression::FunctionExp(f) = &r.expression else {
+        panic!("expected a function, found {:?}", r.expression);
+    };
+    assert!(f.params[0].default.is_none() && f.params[1].default.is_some());
+    let exp = ast.statements[1].expect_expression().unwrap();
+    assert!(matches!(exp, Expression::CallExp(c)
+        if c.args[0].name.is_none() && c.args[1].name.as_deref() == Some("step")));
+    // required params first, positional arguments first
+    assert!(parse_str("fn(a: i32 = 1, b: i32) -> i32 { b }").is_err());
+    let invalid = |program: &str, message: &str| {
+        let err = parse_str(program).unwrap_err();
+        assert!(
+            matches!(err.error_type, ErrorType::InvalidParams),
+            "{:?}",
+            err
+        );
+        assert_eq!(err.message.as_deref(), Some(message));
+    };
+    invalid(
+        "f(a: 1, 2)",
+        "positional arguments must come before named ones, found one after `a`",
+    );
+    invalid("f(a: 1, a: 2)", "argument `a` given more than once");
+    invalid(
+        "f(_, a: 1)",
+        "placeholders cannot be combined with named arguments, like `a`",
+    );
+}
+
+#[test]
+fn parse_maps() {
+    check(parse_str(r#"map<string, i32> m = { "a": 1, "b": 2, }"#));
+    check(parse_str("map m = {:}; let n = { (1, 2): [3] }"));
+    // `map` still names functions
+    check(parse_str(
+        "function map = fn(f: function, l: list) -> list { l }; map(f, l)",
+    ));
+    // a brace followed by an expression and a colon is a map, anything else a block
+    let ast = parse_str("{ x: 1 }").unwrap();
+    let exp = ast.statements[0].expect_expression().unwrap();
+    assert!(matches!(exp, Expression::MapExp(m) if m.entries.len() == 1));
+    let ast = parse_str("{ x }").unwrap();
+    let exp = ast.statements[0].expect_expression().unwrap();
+    assert!(matches!(exp, Expression::BlockExp(_)));
+    assert!(parse_str(r#"let m = { "a": 1 "b": 2 }"#).is_err());
+}
+
+#[test]
+fn parse_imports() {
+    check(parse_str(r#"import "lib.lang" as lib; lib.map(f, l)"#));
+    let ast = parse_str(r#"import { map, filter } from "lib/lists.lang""#).unwrap();
+    assert!(matches!(
+        &ast.statements[0],
+        Statement::Import(Import { names: ImportNames::Selected(names), path, .. })
+            if names.len() == 2 && path == "lib/lists.lang"
+    ));
+    let ast = parse_str("export let x = 1; export enum E { A, B }; let y = 2").unwrap();
+    assert_eq!(ast.exports(), ["x", "A", "B"]);
+    // exports need a declaration, imports a path and both must be at the top level
+    assert!(parse_str("export 1 + 2").is_err());
+    assert!(parse_str("import lib as lib").is_err());
+    assert!(parse_str(r#"import "lib.lang""#).is_err());
+    assert!(parse_str(r#"let f = fn() -> unit { import "lib.lang" as lib }"#).is_err());
+}
+
+#[test]
+fn parse_interpolation() {
+    // lowered into concatenation
+    let ast = parse_str(r#""a${x}b""#).unwrap();
+    let exp = ast.statements[0].expect_expression().unwrap();
+    assert!(matches!(exp, Expression::BinaryExp(_)));
+    let ast = parse_str(r#""${f(1, 2)}""#).unwrap();
+    let exp = ast.statements[0].expect_expression().unwrap();
+    assert!(matches!(exp, Expression::CallExp(_)));
+    assert!(parse_str(r#""${1 2}""#).is_err());
+    // empty or incomplete expressions point at their `${`
+    for program in [r#""a ${}""#, r#""a ${1 +}""#] {
+        let err = parse_str(program).unwrap_err();
+        assert!(matches!(err.error_type, ErrorType::UnexpectedEOF));
+        assert_eq!((err.position.line, err.position.col), (1, 4));
+    }
+}
+
 fn parse_str(program: &str) -> Result<StatementList, Error> {
     let program = program.to_string();
     let tokens = tokenize(program).unwrap();
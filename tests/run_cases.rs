@@ -1,4 +1,9 @@
-use lang::{interpreter::interpret, lexer::tokenize, parser::parse, utils::lang_error::Error};
+use lang::{
+    interpreter::interpret_modules,
+    loader::load,
+    typeck::check_modules,
+    utils::{lang_error::Error, options::Options},
+};
 use std::{fs, path::Path};
 
 #[test]
@@ -20,8 +25,7 @@ fn run_cases(dir: &Path, should_fail: bool) {
             .to_str()
             .expect("failed to get file name")
             .to_string();
-        let program = fs::read_to_string(&path).unwrap();
-        let result = test_exec(program);
+        let result = test_exec(&path);
         println!("\nrunning: {}", name);
         match result {
             Ok(_) => {
@@ -41,9 +45,12 @@ fn run_cases(dir: &Path, should_fail: bool) {
     }
 }
 
-fn test_exec(src: String) -> Result<(), Error> {
-    let tokens = tokenize(src)?;
-    let ast = parse(tokens)?;
-    interpret(ast)?;
+// cases can import the files in ./tests/cases/modules
+fn test_exec(path: &Path) -> Result<(), Error> {
+    let options = Options::default();
+    let mut modules = load(path.to_str().expect("invalid path"), &options)?;
+    // report the first static error, same as `lang run`
+    check_modules(&mut modules, &options).map_err(|mut errors| errors.remove(0))?;
+    interpret_modules(modules, options)?;
     Ok(())
 }
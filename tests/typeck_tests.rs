@@ -0,0 +1,200 @@
+use lang::{
+    lexer::tokenize,
+    parser::{ast::Type, parse},
+    typeck::{check, check_with},
+    utils::{
+        lang_error::{Error, ErrorType},
+        options::Options,
+    },
+};
+
+#[test]
+fn check_valid() {
+    check_str(
+        r#"
+        function add = fn(a: i32, b: i32) -> i32 { a + b }
+        i32 x = add(1, 2)
+        if (x > 2) println(x) else ()
+        "#,
+    )
+    .unwrap_or_else(|e| panic!("check failed: {:?}", e));
+}
+
+#[test]
+fn check_reports_all_errors() {
+    let errors = check_str(
+        r#"
+        i32 x = "a string"
+        if (1) ()
+        fn() -> string { 1 }
+        1 + true
+        "#,
+    )
+    .unwrap_err();
+    assert_eq!(errors.len(), 4);
+    assert_eq!(errors[0].position.line, 2);
+    assert_eq!(errors[3].position.line, 5);
+}
+
+#[test]
+fn check_writes_inferred_types() {
+    let tokens = tokenize("let x = [1]; let f = fn(n) -> i32 { n };".to_string()).unwrap();
+    let mut ast = parse(tokens).unwrap();
+    check(&mut ast).unwrap();
+    let x = ast.statements[0].expect_assignment().unwrap();
+    assert_eq!(x.assignment_type, Type::List(Some(Box::new(Type::I32))));
+    let f = ast.statements[1].expect_assignment().unwrap();
+    assert_eq!(f.assignment_type.display(), "fn(i32) -> i32");
+}
+
+#[test]
+fn check_infers_return_types() {
+    let program = "let f = fn(n: i32) -> _ { n > 0 }; let g = fn(s: string) { s };";
+    let mut ast = parse(tokenize(program.to_string()).unwrap()).unwrap();
+    check(&mut ast).unwrap();
+    let f = ast.statements[0].expect_assignment().unwrap();
+    assert_eq!(f.assignment_type.display(), "fn(i32) -> bool");
+    let g = ast.statements[1].expect_assignment().unwrap();
+    assert_eq!(g.assignment_type.display(), "fn(string) -> string");
+    // a wrong use of the inferred type is still caught
+    assert!(check_str("let f = fn(n: i32) -> _ { n }; string s = f(1)").is_err());
+}
+
+#[test]
+fn check_hides_inference_variables() {
+    let errors = check_str("i32 x = none; let id = fn(v) { v }; i32 y = id").unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].found, "expected i32, found option<_>");
+    assert_eq!(errors[1].found, "expected i32, found fn(_) -> _");
+}
+
+#[test]
+fn check_partial_application_types() {
+    let program = r#"
+    let clamp = fn(low: i32, high: i32, n: f32) -> f32 { n }
+    let a = clamp(0)
+    let b = clamp(_, 10, _)
+    let c = insert({ "a": 1 }, "b")
+    "#;
+    let mut ast = parse(tokenize(program.to_string()).unwrap()).unwrap();
+    check(&mut ast).unwrap();
+    let display = |i: usize| {
+        let a = ast.statements[i].expect_assignment().unwrap();
+        a.assignment_type.display()
+    };
+    assert_eq!(display(1), "fn(i32, f32) -> f32");
+    // placeholders come first, in the order they are written
+    assert_eq!(display(2), "fn(i32, f32) -> f32");
+    assert_eq!(display(3), "fn(i32) -> map<string, i32>");
+}
+
+#[test]
+fn check_reports_missing_case() {
+    let errors = check_str(
+        r#"
+        enum Shape { Circle(f32), Rect(f32, f32), Empty }
+        fn(s: Shape) -> f32 {
+          match (s) { Circle(r) => r, Empty => 0.0 }
+        }
+        "#,
+    )
+    .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].found, "Rect(_, _)");
+}
+
+#[test]
+fn check_unwraps_try() {
+    let errors = check_str(
+        r#"
+        fn(l: list<i32>) -> option<string> {
+          let x = try_head(l)?
+          some(x)
+        }
+        "#,
+    )
+    .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].found,
+        "expected option<string>, found option<i32>"
+    );
+}
+
+#[test]
+fn check_rejects_function_equality() {
+    let errors = check_str(
+        r#"
+        let f = fn(x: i32) -> i32 { x }
+        [f] == [f]
+        f == 1
+        "#,
+    )
+    .unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[0].message.as_deref(),
+        Some("functions cannot be compared")
+    );
+}
+
+#[test]
+fn check_ignores_guarded_arms() {
+    let errors = check_str(
+        r#"
+        fn(l: list<i32>) -> i32 {
+          match l { [] => 0, [x] if x > 0 => x, [x] => 0 - x }
+        }
+        fn(l: list<i32>) -> i32 {
+          match l { [] => 0, h :: t if h > 0 => h }
+        }
+        "#,
+    )
+    .unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].found, "_ :: _ :: _");
+    assert_eq!(errors[1].found, "_ :: _");
+}
+
+#[test]
+fn check_promotion() {
+    let errors = check_str(
+        r#"
+        i64 a = 1i64 + 2i64
+        bigint b = to_bigint(a) % 3
+        i64 c = a + 1
+        f64 d = 1.0 * a
+        bool e = 1 < 2.5
+        let f = b + 1.0
+        let g = a % 2.0
+        f64 h = 1.0
+        i64 i = 1
+        f32 j = 1
+        "#,
+    )
+    .unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].found, "bigint Add f32");
+    assert_eq!(errors[1].found, "i64 Mod f32");
+}
+
+#[test]
+fn check_strict_promotion() {
+    let program = "let a = 1 + 2i64\nlet b = 1.0 == 1.0\nf64 c = 1.0";
+    let tokens = tokenize(program.to_string()).unwrap();
+    let options = Options {
+        strict: true,
+        ..Options::default()
+    };
+    let errors = check_with(&mut parse(tokens).unwrap(), &options).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(errors[0].error_type, ErrorType::ImplicitPromotion));
+    assert_eq!(errors[0].position.col, 11);
+    assert!(matches!(errors[1].error_type, ErrorType::ImplicitPromotion));
+    assert_eq!(errors[1].found, "f32 to f64");
+}
+
+fn check_str(program: &str) -> Result<(), Vec<Error>> {
+    let tokens = tokenize(program.to_string()).unwrap();
+    check(&mut parse(tokens).unwrap())
+}